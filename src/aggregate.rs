@@ -0,0 +1,434 @@
+//! SQL aggregates.
+//!
+//! This module contains the aggregate functions, which compute a single value from many rows,
+//! along with what a query can be grouped by. That is, things that go after the `GROUP BY`
+//! clause, and the conditions after `HAVING` that filter the groups.
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "orders")]
+//! struct Orders {
+//!     user_id: Int,
+//!     total:   Double,
+//! }
+//!
+//! let query = ORDERS
+//!     .select((USER_ID, count(TOTAL), avg(TOTAL), max(TOTAL)))
+//!     .group_by(USER_ID)
+//!     .having(count(TOTAL).greater_than(1))
+//!     .order_by(USER_ID)
+//!     .finish(Sqlite);
+//!
+//! assert_eq!(query.sql, r#"SELECT "user_id", COUNT("total"), AVG("total"), MAX("total") FROM "orders" GROUP BY "user_id" HAVING COUNT("orders"."total") > ? ORDER BY "user_id""#);
+//!
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE orders (user_id INTEGER NOT NULL, total REAL NOT NULL);
+//!                     INSERT INTO orders VALUES (1, 2.0), (1, 4.0), (2, 8.0);")?;
+//!
+//! // Counts are never null, but the other aggregates are null when there are no rows.
+//! let rows: Vec<(i32, i64, Option<f64>, Option<f64>)> = conn.fetch_all(&query)?;
+//! assert_eq!(rows, vec![(1, 2, Some(3.0), Some(4.0))]);
+//! # }
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+//!
+//! Every column of a table has the same type as the others of the same Rust type, so the
+//! compiler can't tell whether a column that isn't aggregated is one of those grouped by.
+//! That is left for the database to check.
+
+use std::marker::PhantomData;
+use super::{
+  builder::Selectable,
+  column::Column,
+  common::{Projection, FromSql, FromSqlError, RowReader, sstr},
+  condition::*,
+  order::{Sort, Direction},
+  render::{Render, Renderer},
+  schema::{DateValue, TimeValue, DateTimeValue},
+  source::Here
+};
+
+/// A trait to represent what a query can be grouped by. Like `OrderBy`, the
+/// parameters are the source of the data and where the columns are found in it.
+pub trait GroupBy<Src, Idx>: Render { }
+
+impl<Src, Table, Type, Idx> GroupBy<Src, Idx> for Column<Table, Type>
+where
+    Column<Table, Type>: Projection<Src, Idx> { }
+
+/// Allows grouping by tuples like (A, B), (A, B, C) ...
+macro_rules! impl_group_by_for_tuple {
+    ($(($tv:ident $ti:ident)),* $(,)?) => {
+        impl<Src, $($tv, $ti),*> GroupBy<Src, ($($ti,)*)> for ($($tv,)*)
+        where
+            $( $tv: GroupBy<Src, $ti>, )* { }
+    };
+}
+
+apply_macro_for_tuples! {
+    impl_group_by_for_tuple! {
+        [(A IA) (B IB)]
+        (C IC) (D ID) (E IE) (F IF) (G IG) (H IH) (I II) (J IJ) (K IK) (L IL) (M IM)
+        (N IN) (O IO) (P IP) (Q IQ) (R IR) (S IS) (T IT) (U IU) (V IV) (W IW) (X IX)
+        (Y IY) (Z IZ)
+    }
+}
+
+/// An aggregate function applied to a column, whose result is of type `Value`.
+pub struct Aggregate<Arg, Value> {
+    pub function: sstr,
+    pub distinct: bool,
+    pub argument: Arg,
+    _value:       PhantomData<fn() -> Value>,
+}
+
+/// `COUNT(*)`, the number of rows.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountAll;
+
+/// Types of values that can be summed and averaged.
+pub trait Numeric {
+    /// The type of the sum of these values, which is wider than the values
+    /// themselves so that it doesn't overflow.
+    type Sum: FromSql;
+}
+
+impl Numeric for i32 { type Sum = i64; }
+impl Numeric for i64 { type Sum = i64; }
+impl Numeric for f32 { type Sum = f64; }
+impl Numeric for f64 { type Sum = f64; }
+
+impl<T: Numeric> Numeric for Option<T> {
+    type Sum = T::Sum;
+}
+
+/// Types of values that also have a type that can hold null. This is the type of
+/// aggregates like `MIN` and `MAX`, which are null when there are no rows.
+pub trait OrNull {
+    /// `Option` of the type, or the type itself if it is already an `Option`.
+    type Value: FromSql;
+}
+
+impl OrNull for bool { type Value = Option<bool>; }
+impl OrNull for i32 { type Value = Option<i32>; }
+impl OrNull for i64 { type Value = Option<i64>; }
+impl OrNull for f32 { type Value = Option<f32>; }
+impl OrNull for f64 { type Value = Option<f64>; }
+impl OrNull for String { type Value = Option<String>; }
+impl OrNull for Vec<u8> { type Value = Option<Vec<u8>>; }
+impl OrNull for DateValue { type Value = Option<DateValue>; }
+impl OrNull for TimeValue { type Value = Option<TimeValue>; }
+impl OrNull for DateTimeValue { type Value = Option<DateTimeValue>; }
+
+impl<T: FromSql> OrNull for Option<T> {
+    type Value = Option<T>;
+}
+
+/// Applies an aggregate function to a column.
+fn aggregate<Arg, Value>(function: sstr, distinct: bool, argument: Arg) -> Aggregate<Arg, Value> {
+    Aggregate {
+        function,
+        distinct,
+        argument,
+        _value: PhantomData,
+    }
+}
+
+/// Counts the rows, as `COUNT(*)`.
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "orders")]
+/// # struct Orders {
+/// #     user_id: Int,
+/// # }
+/// let query = ORDERS
+///     .select((USER_ID, count_all()))
+///     .group_by(USER_ID)
+///     .having(count_all().greater_than(1))
+///     .finish(Postgres);
+///
+/// assert_eq!(query.sql, r#"SELECT "user_id", COUNT(*) FROM "orders" GROUP BY "user_id" HAVING COUNT(*) > $1"#);
+/// ```
+pub fn count_all() -> CountAll {
+    CountAll
+}
+
+/// Counts the rows where a column isn't null.
+pub fn count<Table, Type>(column: Column<Table, Type>) -> Aggregate<Column<Table, Type>, i64> {
+    aggregate("COUNT", false, column)
+}
+
+/// Counts the distinct values of a column, not including null.
+pub fn count_distinct<Table, Type>(column: Column<Table, Type>) -> Aggregate<Column<Table, Type>, i64> {
+    aggregate("COUNT", true, column)
+}
+
+/// Adds up the values of a column.
+pub fn sum<Table, Type>(column: Column<Table, Type>) -> Aggregate<Column<Table, Type>, Option<Type::Sum>>
+where
+    Type: Numeric {
+
+    aggregate("SUM", false, column)
+}
+
+/// Averages the values of a column.
+pub fn avg<Table, Type>(column: Column<Table, Type>) -> Aggregate<Column<Table, Type>, Option<f64>>
+where
+    Type: Numeric {
+
+    aggregate("AVG", false, column)
+}
+
+/// Finds the smallest value of a column.
+pub fn min<Table, Type>(column: Column<Table, Type>) -> Aggregate<Column<Table, Type>, Type::Value>
+where
+    Type: OrNull {
+
+    aggregate("MIN", false, column)
+}
+
+/// Finds the largest value of a column.
+pub fn max<Table, Type>(column: Column<Table, Type>) -> Aggregate<Column<Table, Type>, Type::Value>
+where
+    Type: OrNull {
+
+    aggregate("MAX", false, column)
+}
+
+impl<Table, Type, Value> Aggregate<Column<Table, Type>, Value>
+where
+    Table: Selectable {
+
+    /// Checks to see if the aggregate equals some other value.
+    pub fn equals<Rhs>(self, other: Rhs) -> Equals<Table, Self, Rhs>
+    where
+        Rhs: Operand<Value> {
+
+        Equals {
+            source: Table::default(),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the aggregate is not equal to some other value.
+    pub fn not_equals<Rhs>(self, other: Rhs) -> NotEq<Table, Self, Rhs>
+    where
+        Rhs: Operand<Value> {
+
+        NotEq {
+            source: Table::default(),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the aggregate is greater than some other value.
+    pub fn greater_than<Rhs>(self, other: Rhs) -> Greater<Table, Self, Rhs>
+    where
+        Rhs: Operand<Value> {
+
+        Greater {
+            source: Table::default(),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the aggregate is less than some other value.
+    pub fn less_than<Rhs>(self, other: Rhs) -> Less<Table, Self, Rhs>
+    where
+        Rhs: Operand<Value> {
+
+        Less {
+            source: Table::default(),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the aggregate is less than or equal to some other value.
+    pub fn leq<Rhs>(self, other: Rhs) -> Leq<Table, Self, Rhs>
+    where
+        Rhs: Operand<Value> {
+
+        Leq {
+            source: Table::default(),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the aggregate is greater than or equal to some other value.
+    pub fn geq<Rhs>(self, other: Rhs) -> Geq<Table, Self, Rhs>
+    where
+        Rhs: Operand<Value> {
+
+        Geq {
+            source: Table::default(),
+            projection: self,
+            other,
+        }
+    }
+}
+
+impl<Arg, Value> Aggregate<Arg, Value> {
+    /// Sorts by the aggregate in ascending order.
+    pub fn asc(self) -> Sort<Self> {
+        Sort {
+            projection: self,
+            direction:  Direction::Asc,
+            nulls:      None,
+        }
+    }
+
+    /// Sorts by the aggregate in descending order.
+    pub fn desc(self) -> Sort<Self> {
+        Sort {
+            projection: self,
+            direction:  Direction::Desc,
+            nulls:      None,
+        }
+    }
+}
+
+// `COUNT(*)` doesn't belong to any table, so its comparisons have no source of their own.
+impl CountAll {
+    /// Sorts by the number of rows in ascending order.
+    pub fn asc(self) -> Sort<Self> {
+        Sort {
+            projection: self,
+            direction:  Direction::Asc,
+            nulls:      None,
+        }
+    }
+
+    /// Sorts by the number of rows in descending order.
+    pub fn desc(self) -> Sort<Self> {
+        Sort {
+            projection: self,
+            direction:  Direction::Desc,
+            nulls:      None,
+        }
+    }
+
+    /// Checks to see if the number of rows equals some other value.
+    pub fn equals<Rhs>(self, other: Rhs) -> Equals<(), Self, Rhs>
+    where
+        Rhs: Operand<i64> {
+
+        Equals {
+            source: (),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the number of rows is not equal to some other value.
+    pub fn not_equals<Rhs>(self, other: Rhs) -> NotEq<(), Self, Rhs>
+    where
+        Rhs: Operand<i64> {
+
+        NotEq {
+            source: (),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the number of rows is greater than some other value.
+    pub fn greater_than<Rhs>(self, other: Rhs) -> Greater<(), Self, Rhs>
+    where
+        Rhs: Operand<i64> {
+
+        Greater {
+            source: (),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the number of rows is less than some other value.
+    pub fn less_than<Rhs>(self, other: Rhs) -> Less<(), Self, Rhs>
+    where
+        Rhs: Operand<i64> {
+
+        Less {
+            source: (),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the number of rows is less than or equal to some other value.
+    pub fn leq<Rhs>(self, other: Rhs) -> Leq<(), Self, Rhs>
+    where
+        Rhs: Operand<i64> {
+
+        Leq {
+            source: (),
+            projection: self,
+            other,
+        }
+    }
+
+    /// Checks to see if the number of rows is greater than or equal to some other value.
+    pub fn geq<Rhs>(self, other: Rhs) -> Geq<(), Self, Rhs>
+    where
+        Rhs: Operand<i64> {
+
+        Geq {
+            source: (),
+            projection: self,
+            other,
+        }
+    }
+}
+
+// An aggregate can be projected from wherever its column can,
+// and gives its own type whatever the column's is.
+impl<Src, Arg, Value, Idx> Projection<Src, Idx> for Aggregate<Arg, Value>
+where
+    Arg: Projection<Src, Idx>,
+    Value: FromSql {
+
+    type Value = Value;
+
+    fn decode(&self, row: &mut RowReader) -> Result<Self::Value, FromSqlError> {
+        row.read()
+    }
+}
+
+// An aggregate is in scope wherever its column is, for comparing it in `HAVING`.
+impl<Src, Arg, Value, Idx> InScope<Src, Idx> for Aggregate<Arg, Value>
+where
+    Arg: InScope<Src, Idx> { }
+
+impl<Arg: Render, Value> Render for Aggregate<Arg, Value> {
+    fn render(&self, out: &mut Renderer) {
+        out.push(self.function);
+        out.push(if self.distinct { "(DISTINCT " } else { "(" });
+        out.render(&self.argument);
+        out.push(")");
+    }
+}
+
+impl<Src> Projection<Src, Here> for CountAll {
+    type Value = i64;
+
+    fn decode(&self, row: &mut RowReader) -> Result<Self::Value, FromSqlError> {
+        row.read()
+    }
+}
+
+impl<Src> InScope<Src, Here> for CountAll { }
+
+impl Render for CountAll {
+    fn render(&self, out: &mut Renderer) {
+        out.push("COUNT(*)");
+    }
+}
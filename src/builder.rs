@@ -4,45 +4,80 @@
 //! the beginning, chaining, and finishing of a query. All other modules from this crate
 //! are re-exported to allow for ease of access.
 //!
-//! For example, suppose that there is a table type
-//! `UserTable: Selectable`, with these two fields:
+//! For example, suppose that there is a table `users`, with these two fields:
 //!
 //!   - `id` is a SQL `INTEGER NOT NULL`
 //!   - `login_count` is a SQL `INTEGER`
 //!
+//! The table would be defined like so, which generates the `USERS` constant
+//! to select from and the `ID` and `LOGIN_COUNT` columns:
 //! ```
-//! const USERS: UserTable = UserTable;
-//!
-//! const ID: Column<UserTable, i64> = Column {
-//!     name:     "id",
-//!     position: 0,
-//!     parse:    |s| s.parse().unwrap(),
-//!     _marker:  PhantomData,
-//! };
-//!
-//! const LOGIN_COUNT: Column<UserTable, Option<u64>> = Column {
-//!     name:     "login_count",
-//!     position: 3,
-//!     parse:    |s| s.parse().ok(),
-//!     _marker:  PhantomData,
-//! };
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "UserTable")]
+//! struct Users {
+//!     id:          Int,
+//!     login_count: Nullable<Int>,
+//! }
 //! ```
 //!
 //! Then a query would be constructed like so:
 //! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "UserTable")]
+//! # struct Users {
+//! #     id:          Int,
+//! #     login_count: Nullable<Int>,
+//! # }
 //! let query = USERS
 //!     .select((ID, LOGIN_COUNT))
 //!     .filter(ID.geq(5)
 //!               .and(LOGIN_COUNT.not_null()))
-//!     .finish();
+//!     .finish(Postgres);
+//!
+//! // Values are bound as parameters instead of being written into the SQL.
+//! assert_eq!(query.sql, r#"SELECT "id", "login_count" FROM "UserTable" WHERE "UserTable"."id" >= $1 AND "UserTable"."login_count" IS NOT NULL"#);
+//! assert_eq!(query.params, vec![Value::Int(5)]);
+//! ```
+//!
+//! A field named after a Rust keyword is written as a raw identifier. Its column
+//! and constant are named without the `r#`:
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "items")]
+//! struct Items {
+//!     id:     Int,
+//!     r#type: Text,
+//! }
+//!
+//! let query = ITEMS.select(TYPE).filter(ID.equals(1)).finish(Postgres);
+//! assert_eq!(query.sql, r#"SELECT "type" FROM "items" WHERE "items"."id" = $1"#);
 //! ```
 
 pub use super::{
+    aggregate::*,
     common::*,
     column::*,
-    condition::*
+    condition::*,
+    connection::*,
+    delete::*,
+    dialect::*,
+    expression::*,
+    insert::*,
+    order::*,
+    render::*,
+    schema::*,
+    source::*,
+    update::*
 };
 
+#[cfg(feature = "sqlite")]
+pub use super::sqlite::*;
+
+pub use tuna_derive::{Tuna, Insertable};
+
 // Used when some type needs to remember some other type
 // but without actually storing a value of that type.
 use std::marker::PhantomData;
@@ -54,65 +89,789 @@ pub trait Selectable: ToSql + Default + Sized {
     /// This can be a tuple of fields for a particular table,
     /// or a custom struct for selecting from a particular table,
     /// or any combination thereof.
-    fn select<P: Projection<Self>>(self, p: P) -> Selected<Self, P>;
+    fn select<P, Idx>(self, p: P) -> Selected<Self, P>
+    where
+        P: Projection<Self, Idx> {
+
+        Selected {
+            source:     self,
+            projection: p,
+            distinct:   Distinct::All,
+        }
+    }
+}
+
+/// A `SELECT` statement at any stage of being built.
+///
+/// Building a query moves it through a series of stages, one for each
+/// clause: `Selected`, then `Filtered`, then `Grouped` (and `Having`),
+/// then `Ordered`, then `Limited`.
+/// Any stage after `Selected` can be skipped, but they can't be visited
+/// out of order, so every statement that can be built renders the clauses
+/// in the order SQL expects them. Each stage renders the clauses given so
+/// far, and holds on to the projection so that the finished `Query` can
+/// decode rows with it.
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # struct Users {
+/// #     id: Int,
+/// # }
+/// let query = USERS.select(ID).finish(Sqlite);
+/// assert_eq!(query.sql, r#"SELECT "id" FROM "Users""#);
+///
+/// let query = USERS.select(ID).order_by(ID.desc()).finish(Sqlite);
+/// assert_eq!(query.sql, r#"SELECT "id" FROM "Users" ORDER BY "id" DESC"#);
+/// ```
+pub trait SelectStatement: Render + Sized {
+    /// The table being selected from.
+    type Source;
+
+    /// What is being selected.
+    type Projection;
+
+    /// How duplicate rows are removed, which decides the dialects that the
+    /// statement can be finished for.
+    type Distinct;
+
+    /// Gives up the statement, keeping only its projection.
+    fn into_projection(self) -> Self::Projection;
+
+    /// Finishes constructing a query, rendering it in the given SQL dialect.
+    fn finish<D: Dialect>(self, dialect: D) -> Query<Self::Source, Self::Projection>
+    where
+        Self::Source: SourceFor<D>,
+        Self::Distinct: DistinctFor<D> {
+
+        let mut out = Renderer::new(&dialect);
+        out.render(&self);
+        let (sql, params) = out.into_parts();
+
+        Query {
+            sql,
+            params,
+            conversion: self.into_projection(),
+            _marker:    PhantomData,
+        }
+    }
+}
+
+/// An `INSERT`, `UPDATE` or `DELETE` statement that is ready to be finished.
+pub trait WriteStatement: Render + Sized {
+    /// The table being written to.
+    type Table;
+
+    /// Finishes constructing the statement, rendering it in the given SQL dialect.
+    fn finish<D: Dialect>(self, dialect: D) -> Statement<Self::Table> {
+        let mut out = Renderer::new(&dialect);
+        out.render(&self);
+        let (sql, params) = out.into_parts();
+
+        Statement {
+            sql,
+            params,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Makes the statement return a row for each row it wrote, with the
+    /// columns of the given projection as they are after writing.
+    fn returning<P, Idx>(self, projection: P) -> Returning<Self, P>
+    where
+        P: Projection<Self::Table, Idx> {
+
+        Returning {
+            statement: self,
+            projection,
+        }
+    }
+}
+
+/// The stages of a statement that can still be grouped.
+pub trait Groupable: SelectStatement {
+    /// Groups the rows by one or more columns of the source, so that
+    /// aggregates are computed for each group.
+    fn group_by<Keys, Idx>(self, keys: Keys) -> Grouped<Self, Keys>
+    where
+        Keys: GroupBy<Self::Source, Idx> {
+
+        Grouped {
+            statement: self,
+            keys,
+        }
+    }
+}
+
+/// The stages of a statement that can still be sorted.
+pub trait Orderable: SelectStatement {
+    /// Sorts the rows by one or more columns of the source.
+    fn order_by<Order, Idx>(self, ordering: Order) -> Ordered<Self, Order>
+    where
+        Order: OrderBy<Self::Source, Idx> {
+
+        Ordered {
+            statement: self,
+            ordering,
+        }
+    }
+}
+
+/// The stages of a statement that can still have their rows limited.
+pub trait Limitable: SelectStatement {
+    /// Returns at most `n` rows.
+    fn limit(self, n: u64) -> Limited<Self> {
+        Limited {
+            statement: self,
+            limit:     Some(n),
+            offset:    None,
+        }
+    }
+
+    /// Skips the first `n` rows.
+    fn offset(self, n: u64) -> Limited<Self> {
+        Limited {
+            statement: self,
+            limit:     None,
+            offset:    Some(n),
+        }
+    }
 }
 
 /// The result of a selection that has yet to be filtered. The query at this
-/// point is basically `SELECT self.projection FROM self.source`.
-pub struct Selected<Src, Prj> {
+/// point is basically `SELECT self.distinct self.projection FROM self.source`.
+pub struct Selected<Src, Prj, Dist = Distinct> {
     pub source: Src,
     pub projection: Prj,
+    pub distinct: Dist,
+}
+
+/// Which of the selected rows are kept when some of them are the same.
+pub enum Distinct {
+    /// Every row is kept.
+    All,
+
+    /// Only one of each set of identical rows is kept.
+    Rows,
+}
+
+/// Only the first row of each set of rows that agree on the given columns
+/// is kept. This is only supported by Postgres.
+pub struct DistinctOn<Cols> {
+    pub columns: Cols,
+}
+
+impl Render for Distinct {
+    fn render(&self, out: &mut Renderer) {
+        match self {
+            Distinct::All => (),
+            Distinct::Rows => out.push("DISTINCT "),
+        }
+    }
 }
 
+impl<Cols: Render> Render for DistinctOn<Cols> {
+    fn render(&self, out: &mut Renderer) {
+        out.push("DISTINCT ON (");
+        out.render(&self.columns);
+        out.push(") ");
+    }
+}
+
+/// Ways of removing duplicate rows that can be written in the dialect `D`.
+pub trait DistinctFor<D> { }
+
+impl<D> DistinctFor<D> for Distinct { }
+impl<Cols, D: SupportsDistinctOn> DistinctFor<D> for DistinctOn<Cols> { }
+
+/// Statements that can be used as a subquery. A subquery is rendered along
+/// with the query around it, in whichever dialect that one is finished for,
+/// so it can only use what every dialect supports.
+pub trait Subquery: SelectStatement { }
+
+impl<Stmt> Subquery for Stmt
+where
+    Stmt: SelectStatement,
+    Stmt::Source: SourceFor<AnyDialect>,
+    Stmt::Distinct: DistinctFor<AnyDialect> { }
+
+/// Subqueries that can be used with `in_query`. MySQL doesn't support `LIMIT`
+/// in a subquery that `IN` checks against, so a `Limited` statement isn't one
+/// of them, though it can still be used with `exists`:
+///
+/// ```compile_fail
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # struct Users {
+/// #     id: Int,
+/// # }
+/// let first = USERS.select(ID).order_by(ID).limit(1);
+/// let query = USERS.select(ID).filter(ID.in_query(first)).finish(Sqlite);
+/// ```
+pub trait InSubquery: Subquery { }
+
+impl<Src, Prj, Dist> InSubquery for Selected<Src, Prj, Dist> where Self: Subquery { }
+impl<Src, Prj, Cond, Dist> InSubquery for Filtered<Src, Prj, Cond, Dist> where Self: Subquery { }
+
+impl<Stmt, Keys> InSubquery for Grouped<Stmt, Keys>
+where
+    Stmt: InSubquery,
+    Self: Subquery { }
+
+impl<Stmt, Cond> InSubquery for Having<Stmt, Cond>
+where
+    Stmt: InSubquery,
+    Self: Subquery { }
+
+impl<Stmt, Order> InSubquery for Ordered<Stmt, Order>
+where
+    Stmt: InSubquery,
+    Self: Subquery { }
+
 impl<Src, Prj> Selected<Src, Prj> {
-    /// Filters a selection by some given condition.
-    pub fn filter<Cond>(self, condition: Cond) -> Filtered<Src, Prj, Cond>
+    /// Removes duplicate rows from the selection, as `SELECT DISTINCT`.
+    pub fn distinct(self) -> Self {
+        Selected { distinct: Distinct::Rows, ..self }
+    }
+
+    /// Keeps only the first row of each set of rows that agree on the given
+    /// columns, as Postgres' `SELECT DISTINCT ON`. Which row is first is
+    /// decided by the `ORDER BY`, which has to start with the same columns.
+    ///
+    /// ```
+    /// # use tuna::builder::*;
+    /// # #[derive(Tuna)]
+    /// # #[tuna(table = "logins")]
+    /// # struct Logins {
+    /// #     user_id: Int,
+    /// #     at:      DateTime,
+    /// # }
+    /// let query = LOGINS
+    ///     .select((USER_ID, AT))
+    ///     .distinct_on(USER_ID)
+    ///     .order_by((USER_ID, AT.desc()))
+    ///     .finish(Postgres);
+    ///
+    /// assert_eq!(query.sql, r#"SELECT DISTINCT ON ("user_id") "user_id", "at" FROM "logins" ORDER BY "user_id", "at" DESC"#);
+    /// ```
+    ///
+    /// No other database has `DISTINCT ON`, so the query can only be finished
+    /// for `Postgres`:
+    ///
+    /// ```compile_fail
+    /// # use tuna::builder::*;
+    /// # #[derive(Tuna)]
+    /// # #[tuna(table = "logins")]
+    /// # struct Logins {
+    /// #     user_id: Int,
+    /// # }
+    /// let query = LOGINS.select(USER_ID).distinct_on(USER_ID).finish(Sqlite);
+    /// ```
+    pub fn distinct_on<Cols, Idx>(self, columns: Cols) -> Selected<Src, Prj, DistinctOn<Cols>>
     where
-        Cond: Condition<Src> {
+        Cols: GroupBy<Src, Idx> {
 
-        Filtered {
+        Selected {
             source:     self.source,
             projection: self.projection,
+            distinct:   DistinctOn { columns },
+        }
+    }
+}
+
+impl<Src, Prj, Dist> Selected<Src, Prj, Dist> {
+    /// Filters a selection by some given condition.
+    pub fn filter<Cond, Idx>(self, condition: Cond) -> Filtered<Src, Prj, Cond, Dist>
+    where
+        Cond: Condition<Src, Idx> {
+
+        Filtered {
+            selected: self,
             condition,
         }
     }
 }
 
+impl<Src, Prj, Dist> Render for Selected<Src, Prj, Dist>
+where
+    Src: Source,
+    Prj: Render,
+    Dist: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        if Src::JOINED {
+            out.qualify_columns();
+        }
+        out.push("SELECT ");
+        out.render(&self.distinct);
+        out.render(&self.projection);
+        out.push(" FROM ");
+        self.source.render_source(out);
+    }
+}
+
+impl<Src, Prj, Dist> SelectStatement for Selected<Src, Prj, Dist>
+where
+    Src: Source,
+    Prj: Render,
+    Dist: Render {
+
+    type Source = Src;
+    type Projection = Prj;
+    type Distinct = Dist;
+
+    fn into_projection(self) -> Prj {
+        self.projection
+    }
+}
+
+impl<Src: Source, Prj: Render, Dist: Render> Groupable for Selected<Src, Prj, Dist> { }
+impl<Src: Source, Prj: Render, Dist: Render> Orderable for Selected<Src, Prj, Dist> { }
+impl<Src: Source, Prj: Render, Dist: Render> Limitable for Selected<Src, Prj, Dist> { }
+
 /// The result of applying filtering. The query at this
 /// point is `SELECT self.projection FROM self.source WHERE self.condition`.
-pub struct Filtered<Src, Prj, Cond> {
-    source:     Src,
-    projection: Prj,
-    condition:  Cond,
+pub struct Filtered<Src, Prj, Cond, Dist = Distinct> {
+    selected:  Selected<Src, Prj, Dist>,
+    condition: Cond,
 }
 
-impl<Src, Prj, Cond> Filtered<Src, Prj, Cond> {
-    /// Finishes constructing a query.
-    pub fn finish(self) -> Query<Src, Prj>
+impl<Src, Prj, Cond, Dist> Filtered<Src, Prj, Cond, Dist>
+where
+    Cond: Predicate {
+
+    /// Filters the selection further, keeping only the rows that meet both
+    /// the conditions so far and the given one.
+    ///
+    /// ```
+    /// # use tuna::builder::*;
+    /// # #[derive(Tuna)]
+    /// # struct Users {
+    /// #     id:          Int,
+    /// #     login_count: Int,
+    /// # }
+    /// let query = USERS
+    ///     .select(ID)
+    ///     .filter(ID.greater_than(10))
+    ///     .or_filter(ID.equals(1))
+    ///     .filter(LOGIN_COUNT.geq(3))
+    ///     .finish(Postgres);
+    ///
+    /// assert_eq!(query.sql, r#"SELECT "id" FROM "Users" WHERE ("Users"."id" > $1 OR "Users"."id" = $2) AND "Users"."login_count" >= $3"#);
+    /// assert_eq!(query.params, vec![Value::Int(10), Value::Int(1), Value::Int(3)]);
+    /// ```
+    pub fn filter<Other, Idx>(self, condition: Other) -> Filtered<Src, Prj, Both<Cond, Other>, Dist>
     where
-        Src: Selectable,
-        Prj: Projection<Src>,
-        Cond: Condition<Src> {
+        Other: Condition<Src, Idx> {
 
-        let sql = format!("SELECT {} FROM {} WHERE {}",
-                          self.projection.sql(),
-                          self.source.sql(),
-                          self.condition.sql());
+        Filtered {
+            selected:  self.selected,
+            condition: self.condition.and(condition),
+        }
+    }
+
+    /// Widens the selection, keeping the rows that meet either the conditions
+    /// so far or the given one.
+    pub fn or_filter<Other, Idx>(self, condition: Other) -> Filtered<Src, Prj, Either<Cond, Other>, Dist>
+    where
+        Other: Condition<Src, Idx> {
+
+        Filtered {
+            selected:  self.selected,
+            condition: self.condition.or(condition),
+        }
+    }
+}
+
+impl<Src, Prj, Cond, Dist> Render for Filtered<Src, Prj, Cond, Dist>
+where
+    Src: Source,
+    Prj: Render,
+    Cond: Render,
+    Dist: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.selected);
+        out.push(" WHERE ");
+        out.render(&self.condition);
+    }
+}
+
+impl<Src, Prj, Cond, Dist> SelectStatement for Filtered<Src, Prj, Cond, Dist>
+where
+    Src: Source,
+    Prj: Render,
+    Cond: Render,
+    Dist: Render {
+
+    type Source = Src;
+    type Projection = Prj;
+    type Distinct = Dist;
+
+    fn into_projection(self) -> Prj {
+        self.selected.projection
+    }
+}
+
+impl<Src: Source, Prj: Render, Cond: Render, Dist: Render> Groupable for Filtered<Src, Prj, Cond, Dist> { }
+impl<Src: Source, Prj: Render, Cond: Render, Dist: Render> Orderable for Filtered<Src, Prj, Cond, Dist> { }
+impl<Src: Source, Prj: Render, Cond: Render, Dist: Render> Limitable for Filtered<Src, Prj, Cond, Dist> { }
+
+/// The result of grouping. The query at this point is
+/// `self.statement GROUP BY self.keys`.
+pub struct Grouped<Stmt, Keys> {
+    statement: Stmt,
+    keys:      Keys,
+}
+
+impl<Stmt, Keys> Grouped<Stmt, Keys>
+where
+    Stmt: SelectStatement {
+
+    /// Filters the groups by some given condition, which is usually on aggregates.
+    pub fn having<Cond, Idx>(self, condition: Cond) -> Having<Self, Cond>
+    where
+        Cond: GroupCondition<Stmt::Source, Idx> {
+
+        Having {
+            statement: self,
+            condition,
+        }
+    }
+}
+
+impl<Stmt, Keys> Render for Grouped<Stmt, Keys>
+where
+    Stmt: Render,
+    Keys: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.statement);
+        out.push(" GROUP BY ");
+        out.render(&self.keys);
+    }
+}
+
+impl<Stmt, Keys> SelectStatement for Grouped<Stmt, Keys>
+where
+    Stmt: SelectStatement,
+    Keys: Render {
+
+    type Source = Stmt::Source;
+    type Projection = Stmt::Projection;
+    type Distinct = Stmt::Distinct;
+
+    fn into_projection(self) -> Self::Projection {
+        self.statement.into_projection()
+    }
+}
+
+impl<Stmt: SelectStatement, Keys: Render> Orderable for Grouped<Stmt, Keys> { }
+impl<Stmt: SelectStatement, Keys: Render> Limitable for Grouped<Stmt, Keys> { }
+
+/// The result of filtering groups. The query at this point is
+/// `self.statement HAVING self.condition`.
+pub struct Having<Stmt, Cond> {
+    statement: Stmt,
+    condition: Cond,
+}
+
+impl<Stmt, Cond> Having<Stmt, Cond>
+where
+    Stmt: SelectStatement,
+    Cond: Predicate {
+
+    /// Filters the groups further, keeping only the ones that meet both
+    /// the conditions so far and the given one.
+    pub fn having<Other, Idx>(self, condition: Other) -> Having<Stmt, Both<Cond, Other>>
+    where
+        Other: GroupCondition<Stmt::Source, Idx> {
+
+        Having {
+            statement: self.statement,
+            condition: self.condition.and(condition),
+        }
+    }
+}
+
+impl<Stmt, Cond> Render for Having<Stmt, Cond>
+where
+    Stmt: Render,
+    Cond: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.statement);
+        out.push(" HAVING ");
+        out.render(&self.condition);
+    }
+}
+
+impl<Stmt, Cond> SelectStatement for Having<Stmt, Cond>
+where
+    Stmt: SelectStatement,
+    Cond: Render {
+
+    type Source = Stmt::Source;
+    type Projection = Stmt::Projection;
+    type Distinct = Stmt::Distinct;
+
+    fn into_projection(self) -> Self::Projection {
+        self.statement.into_projection()
+    }
+}
+
+impl<Stmt: SelectStatement, Cond: Render> Orderable for Having<Stmt, Cond> { }
+impl<Stmt: SelectStatement, Cond: Render> Limitable for Having<Stmt, Cond> { }
+
+/// The result of sorting. The query at this point is
+/// `self.statement ORDER BY self.ordering`.
+pub struct Ordered<Stmt, Order> {
+    statement: Stmt,
+    ordering:  Order,
+}
+
+impl<Stmt, Order> Render for Ordered<Stmt, Order>
+where
+    Stmt: Render,
+    Order: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.statement);
+        out.push(" ORDER BY ");
+        out.render(&self.ordering);
+    }
+}
+
+impl<Stmt, Order> SelectStatement for Ordered<Stmt, Order>
+where
+    Stmt: SelectStatement,
+    Order: Render {
+
+    type Source = Stmt::Source;
+    type Projection = Stmt::Projection;
+    type Distinct = Stmt::Distinct;
+
+    fn into_projection(self) -> Self::Projection {
+        self.statement.into_projection()
+    }
+}
+
+impl<Stmt: SelectStatement, Order: Render> Limitable for Ordered<Stmt, Order> { }
+
+/// The result of limiting the number of rows. The query at this point is
+/// `self.statement LIMIT self.limit OFFSET self.offset`, in whatever
+/// form the dialect limits rows in.
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # struct Users {
+/// #     id: Int,
+/// # }
+/// let page = || USERS.select(ID).order_by(ID.asc()).limit(10).offset(20);
+///
+/// let query = page().finish(Postgres);
+/// assert_eq!(query.sql, r#"SELECT "id" FROM "Users" ORDER BY "id" ASC LIMIT $1 OFFSET $2"#);
+/// assert_eq!(query.params, vec![Value::Int(10), Value::Int(20)]);
+///
+/// // A dialect that keeps to standard SQL.
+/// struct Standard;
+/// impl Dialect for Standard { }
+///
+/// let query = page().finish(Standard);
+/// assert_eq!(query.sql, r#"SELECT "id" FROM "Users" ORDER BY "id" ASC OFFSET ? ROWS FETCH NEXT ? ROWS ONLY"#);
+/// assert_eq!(query.params, vec![Value::Int(20), Value::Int(10)]);
+///
+/// // SQLite and MySQL need a limit to skip rows.
+/// let query = USERS.select(ID).offset(5).finish(Sqlite);
+/// assert_eq!(query.sql, r#"SELECT "id" FROM "Users" LIMIT -1 OFFSET ?"#);
+/// ```
+pub struct Limited<Stmt> {
+    statement: Stmt,
+    limit:     Option<u64>,
+    offset:    Option<u64>,
+}
+
+impl<Stmt> Limited<Stmt> {
+    /// Returns at most `n` rows.
+    pub fn limit(self, n: u64) -> Self {
+        Limited { limit: Some(n), ..self }
+    }
+
+    /// Skips the first `n` rows.
+    pub fn offset(self, n: u64) -> Self {
+        Limited { offset: Some(n), ..self }
+    }
+}
+
+impl<Stmt: Render> Render for Limited<Stmt> {
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.statement);
+
+        // Row counts are bound like any other value.
+        let count = |n: u64| Value::Int(n.min(i64::MAX as u64) as i64);
+
+        match out.dialect().limit_syntax() {
+            LimitSyntax::FetchNext => {
+                if let Some(offset) = self.offset {
+                    out.push(" OFFSET ");
+                    out.push_param(count(offset));
+                    out.push(" ROWS");
+                }
+                if let Some(limit) = self.limit {
+                    out.push(" FETCH NEXT ");
+                    out.push_param(count(limit));
+                    out.push(" ROWS ONLY");
+                }
+            }
+            LimitSyntax::LimitOffset { unbounded } => {
+                match (self.limit, unbounded) {
+                    (Some(limit), _) => {
+                        out.push(" LIMIT ");
+                        out.push_param(count(limit));
+                    }
+                    (None, Some(all)) if self.offset.is_some() => {
+                        out.push(" LIMIT ");
+                        out.push(all);
+                    }
+                    _ => (),
+                }
+                if let Some(offset) = self.offset {
+                    out.push(" OFFSET ");
+                    out.push_param(count(offset));
+                }
+            }
+        }
+    }
+}
+
+impl<Stmt: SelectStatement> SelectStatement for Limited<Stmt> {
+    type Source = Stmt::Source;
+    type Projection = Stmt::Projection;
+    type Distinct = Stmt::Distinct;
+
+    fn into_projection(self) -> Self::Projection {
+        self.statement.into_projection()
+    }
+}
+
+/// A write statement that returns rows. The statement at this point is
+/// `self.statement RETURNING self.projection`. Finishing it gives a
+/// `Query`, whose rows decode into the projection's Rust type like a
+/// `SELECT`'s do.
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     id:          Int,
+/// #     name:        Text,
+/// #     login_count: Int,
+/// # }
+/// # #[cfg(feature = "sqlite")] {
+/// let conn = SqliteConnection::open_in_memory()?;
+/// conn.execute_batch("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, login_count INTEGER NOT NULL);")?;
+///
+/// let insert = insert_into(USERS)
+///     .values(((NAME, "tuna".to_owned()), (LOGIN_COUNT, 0)))
+///     .returning(ID)
+///     .finish(Sqlite);
+///
+/// assert_eq!(insert.sql, r#"INSERT INTO "users" ("name", "login_count") VALUES (?, ?) RETURNING "id""#);
+/// let id: i32 = conn.fetch_one(&insert)?;
+///
+/// let update = update(USERS)
+///     .set(LOGIN_COUNT, LOGIN_COUNT + 1)
+///     .filter(ID.equals(id))
+///     .returning((NAME, LOGIN_COUNT))
+///     .finish(Sqlite);
+///
+/// assert_eq!(conn.fetch_all(&update)?, vec![("tuna".to_owned(), 1)]);
+/// # }
+/// # Ok::<(), tuna::connection::Error>(())
+/// ```
+///
+/// MySQL has no `RETURNING`, so the statement can't be finished for it:
+///
+/// ```compile_fail
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     id: Int,
+/// # }
+/// let delete = delete_from(USERS).filter(ID.equals(1)).returning(ID).finish(MySql);
+/// ```
+pub struct Returning<Stmt, Prj> {
+    statement:  Stmt,
+    projection: Prj,
+}
+
+impl<Stmt, Prj> Returning<Stmt, Prj>
+where
+    Stmt: WriteStatement,
+    Prj: Render {
+
+    /// Finishes constructing the statement, rendering it in the given SQL dialect.
+    pub fn finish<D: SupportsReturning>(self, dialect: D) -> Query<Stmt::Table, Prj> {
+        let mut out = Renderer::new(&dialect);
+        out.render(&self);
+        let (sql, params) = out.into_parts();
 
         Query {
             sql,
+            params,
             conversion: self.projection,
             _marker:    PhantomData,
         }
     }
 }
 
+impl<Stmt, Prj> Render for Returning<Stmt, Prj>
+where
+    Stmt: Render,
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.statement);
+        out.push(" RETURNING ");
+        out.render(&self.projection);
+    }
+}
+
 /// A query that’s ready to execute. It no longer stores any data
 /// pointing to the source table, but it’s still tied by type so that
 /// you can only execute it on a connection that has that table.
+///
+/// Values compared against in the query are not part of `sql`; each
+/// one is a placeholder whose value is found at the same position
+/// in `params`.
 #[derive(Debug)]
 pub struct Query<Src, Prj> {
     pub sql:    String,
+    pub params: Vec<Value>,
     conversion: Prj,
     _marker:    PhantomData<fn(&Src)>,
-}
\ No newline at end of file
+}
+
+impl<Src, Prj> Query<Src, Prj> {
+    /// Decodes a row returned by running this query into the Rust
+    /// type of its projection.
+    pub fn decode<Idx>(&self, row: Row) -> Result<Prj::Value, FromSqlError>
+    where
+        Prj: Projection<Src, Idx> {
+
+        self.conversion.decode(&mut RowReader::new(row))
+    }
+}
+
+/// A statement that’s ready to execute but doesn’t return rows, like an
+/// `INSERT`. Running it on a `Connection` gives the number of rows it
+/// changed. Like `Query`, it’s tied by type to the table it changes.
+#[derive(Debug)]
+pub struct Statement<Table> {
+    pub sql:    String,
+    pub params: Vec<Value>,
+    _marker:    PhantomData<fn(&Table)>,
+}
@@ -1,14 +1,18 @@
 //! SQL table column.
 //!
 //! This module contains the structure and interface for dealing with columns in SQL tables.
-//! `Condition`s can be called on `Column`s and logic dealing with parsing data coming back from
-//! SQL is also handled here (not yet implemented).
+//! `Condition`s can be called on `Column`s, and a projected `Column` decodes the data coming
+//! back from SQL into its Rust type with `FromSql`.
 
 use std::marker::PhantomData;
 use super::{
+  aggregate::OrNull,
   condition::*,
-  builder::Selectable,
-  common::{ToSql, Projection, sstr}
+  builder::{Selectable, InSubquery},
+  common::{ToSql, Projection, FromSql, FromSqlError, RowReader, TextValue, NonNull, sstr},
+  order::{Sort, Direction},
+  source::{Includes, Nullness},
+  render::{Render, Renderer}
 };
 
 /// Represents some column of a table whose values convert to a
@@ -24,20 +28,20 @@ use super::{
 /// Then you would want to define columns like so:
 ///
 /// ```
+/// # use std::marker::PhantomData;
+/// # use tuna::builder::*;
 /// struct UserTable;
 ///
 /// const ID: Column<UserTable, i64> = Column {
-///     name:     "id",
-///     position: 0,
-///     parse:    |s| s.parse().unwrap(), // parsing for now
-///     _marker:  PhantomData,
+///     name:          "id",
+///     position:      0,
+///     _table_marker: PhantomData,
 /// };
 ///
-/// const LOGIN_COUNT: Column<UserTable, Option<u64>> = Column {
-///     name:     "login_count",
-///     position: 3,
-///     parse:    |s| s.parse().ok(), // parsing for now
-///     _marker:  PhantomData,
+/// const LOGIN_COUNT: Column<UserTable, Option<i64>> = Column {
+///     name:          "login_count",
+///     position:      1,
+///     _table_marker: PhantomData,
 /// };
 /// ```
 #[derive(Clone)]
@@ -48,23 +52,24 @@ pub struct Column<Table, Type> {
     /// The zero based index of the column in the table.
     pub position: usize,
 
-    /// How to data from this column into a Rust Type
-    pub parse:    for <'a> fn(&'a str) -> Type,
-
-    /// Keeps track of the source of data for this column
-    pub _table_marker:  PhantomData<fn(&Table)>,
+    /// Keeps track of the source of data for this column, and the
+    /// Rust type its data decodes into
+    pub _table_marker:  PhantomData<fn(&Table) -> Type>,
 }
 
 impl<Table, Type> Column<Table, Type>
 where
     Table: Selectable {
 
-    /// Checks to see if the column has data that equals some other value.
-    pub fn equals(self, other: Type) -> Equals<Table, Self, Type> {
+    /// Checks to see if the column has data that equals some other value or column.
+    pub fn equals<Rhs>(self, other: Rhs) -> Equals<Table, Self, Rhs>
+    where
+        Rhs: Operand<Type> {
+
         Equals {
             source: Table::default(),
             projection: self,
-            value: other,
+            other,
         }
     }
 
@@ -84,65 +89,279 @@ where
         }
     }
 
-    /// Checks to see if the column has data that is greater than some other value.
-    pub fn greater_than(self, other: Type) -> Greater<Table, Self, Type> {
+    /// Checks to see if the column has data that is greater than some other value or column.
+    pub fn greater_than<Rhs>(self, other: Rhs) -> Greater<Table, Self, Rhs>
+    where
+        Rhs: Operand<Type> {
+
         Greater {
             source: Table::default(),
             projection: self,
-            value: other,
+            other,
         }
     }
 
-    /// Checks to see if the column has data that is less than some other value.
-    pub fn less_than(self, other: Type) -> Less<Table, Self, Type> {
+    /// Checks to see if the column has data that is less than some other value or column.
+    pub fn less_than<Rhs>(self, other: Rhs) -> Less<Table, Self, Rhs>
+    where
+        Rhs: Operand<Type> {
+
         Less {
             source: Table::default(),
             projection: self,
-            value: other,
+            other,
         }
     }
 
-    /// Checks to see if the column has data that is less than or equal to some other value.
-    pub fn leq(self, other: Type) -> Leq<Table, Self, Type> {
+    /// Checks to see if the column has data that is less than or equal to some other value or column.
+    pub fn leq<Rhs>(self, other: Rhs) -> Leq<Table, Self, Rhs>
+    where
+        Rhs: Operand<Type> {
+
         Leq {
             source: Table::default(),
             projection: self,
-            value: other,
+            other,
         }
     }
 
-    /// Checks to see if the column has data that is greater than or equal to some other value.
-    pub fn geq(self, other: Type) -> Geq<Table, Self, Type> {
+    /// Checks to see if the column has data that is greater than or equal to some other value or column.
+    pub fn geq<Rhs>(self, other: Rhs) -> Geq<Table, Self, Rhs>
+    where
+        Rhs: Operand<Type> {
+
         Geq {
             source: Table::default(),
             projection: self,
-            value: other,
+            other,
         }
     }
 
-    /// Checks to see if the column has data that is not equal to some other value.
-    pub fn not_equals(self, other: Type) -> NotEq<Table, Self, Type> {
+    /// Checks to see if the column has data that is not equal to some other value or column.
+    pub fn not_equals<Rhs>(self, other: Rhs) -> NotEq<Table, Self, Rhs>
+    where
+        Rhs: Operand<Type> {
+
         NotEq {
             source: Table::default(),
             projection: self,
-            value: other,
+            other,
+        }
+    }
+
+    /// Checks to see if the column has data that equals any of the given values.
+    pub fn is_in<I>(self, values: I) -> In<Table, Self, Type>
+    where
+        I: IntoIterator<Item = Type> {
+
+        In {
+            source: Table::default(),
+            projection: self,
+            values: values.into_iter().collect(),
+        }
+    }
+
+    /// Checks to see if the column has data that equals none of the given values.
+    pub fn not_in<I>(self, values: I) -> NotIn<Table, Self, Type>
+    where
+        I: IntoIterator<Item = Type> {
+
+        NotIn {
+            source: Table::default(),
+            projection: self,
+            values: values.into_iter().collect(),
+        }
+    }
+
+    /// Checks to see if the column has data that equals any of the values returned
+    /// by a subquery, which has to select a single column of the same type.
+    pub fn in_query<Sub, Idx>(self, query: Sub) -> InQuery<Table, Self, Sub>
+    where
+        Sub: InSubquery,
+        Sub::Projection: Projection<Sub::Source, Idx, Value = Type> {
+
+        InQuery {
+            source: Table::default(),
+            projection: self,
+            query,
+        }
+    }
+
+    /// Checks to see if the column has data between `low` and `high`, inclusive.
+    /// The bounds can't be null, even for a nullable column.
+    pub fn between(self, low: Type::Value, high: Type::Value) -> Between<Table, Self, Type::Value>
+    where
+        Type: NonNull {
+
+        Between {
+            source: Table::default(),
+            projection: self,
+            low,
+            high,
+        }
+    }
+
+    /// Checks to see if the column has data outside of `low` to `high`, which
+    /// can't be null either.
+    pub fn not_between(self, low: Type::Value, high: Type::Value) -> NotBetween<Table, Self, Type::Value>
+    where
+        Type: NonNull {
+
+        NotBetween {
+            source: Table::default(),
+            projection: self,
+            low,
+            high,
+        }
+    }
+
+    /// Sorts by the column in ascending order.
+    pub fn asc(self) -> Sort<Self> {
+        Sort {
+            projection: self,
+            direction:  Direction::Asc,
+            nulls:      None,
+        }
+    }
+
+    /// Sorts by the column in descending order.
+    pub fn desc(self) -> Sort<Self> {
+        Sort {
+            projection: self,
+            direction:  Direction::Desc,
+            nulls:      None,
+        }
+    }
+}
+
+impl<Table, Type> Column<Table, Type>
+where
+    Table: Selectable,
+    Type: TextValue {
+
+    /// Checks to see if the column has data matching a `LIKE` pattern.
+    pub fn like(self, pattern: &str) -> Like<Table, Self> {
+        Like {
+            source: Table::default(),
+            projection: self,
+            pattern: pattern.to_owned(),
+            escape: None,
+        }
+    }
+
+    /// Checks to see if the column has data not matching a `LIKE` pattern.
+    pub fn not_like(self, pattern: &str) -> NotLike<Table, Self> {
+        NotLike {
+            source: Table::default(),
+            projection: self,
+            pattern: pattern.to_owned(),
+            escape: None,
+        }
+    }
+
+    /// Checks to see if the column has data matching a `LIKE` pattern, ignoring case.
+    pub fn ilike(self, pattern: &str) -> ILike<Table, Self> {
+        ILike {
+            source: Table::default(),
+            projection: self,
+            pattern: pattern.to_owned(),
+            escape: None,
+        }
+    }
+
+    /// Checks to see if the column has data beginning with `prefix`.
+    pub fn starts_with(self, prefix: &str) -> Like<Table, Self> {
+        self.escaped_like(format!("{}%", escape_like(prefix)))
+    }
+
+    /// Checks to see if the column has data ending with `suffix`.
+    pub fn ends_with(self, suffix: &str) -> Like<Table, Self> {
+        self.escaped_like(format!("%{}", escape_like(suffix)))
+    }
+
+    /// Checks to see if the column has data containing `infix`.
+    pub fn contains(self, infix: &str) -> Like<Table, Self> {
+        self.escaped_like(format!("%{}%", escape_like(infix)))
+    }
+
+    fn escaped_like(self, pattern: String) -> Like<Table, Self> {
+        Like {
+            source: Table::default(),
+            projection: self,
+            pattern,
+            escape: Some(LIKE_ESCAPE),
         }
     }
 }
 
-// You can project a column from its table, and it gives you the
-// columns type:
-impl<Table, Type> Projection<Table> for Column<Table, Type> {
-    type Value = Type;
+/// The character used to escape user input in `LIKE` patterns. Backslash would be
+/// the obvious choice, but MySQL treats it specially inside string literals.
+const LIKE_ESCAPE: char = '!';
+
+/// Escapes the characters that are special in `LIKE` patterns.
+fn escape_like(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        if c == '%' || c == '_' || c == LIKE_ESCAPE {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+// A column can be compared against another column of the same
+// type, as long as its table is part of the query's source.
+impl<Table, Type> Operand<Type> for Column<Table, Type>
+where
+    Table: Selectable {
+
+    fn render_operand(&self, out: &mut Renderer) {
+        out.render_qualified(self);
+    }
+}
+
+impl<Src, Table, Type, Idx> InScope<Src, Idx> for Column<Table, Type>
+where
+    Src: Includes<Table, Idx> { }
+
+// You can project a column from any source that includes its
+// table, and it gives you the column's type. Columns of outer
+// joined tables can be missing, so they give an `Option` of it.
+impl<Src, Table, Type, Idx> Projection<Src, Idx> for Column<Table, Type>
+where
+    Src: Includes<Table, Idx>,
+    Table: Selectable,
+    Type: OrNull,
+    <Src::Nulls as Nullness>::Wrap<Type>: FromSql {
+
+    type Value = <Src::Nulls as Nullness>::Wrap<Type>;
+
+    fn decode(&self, row: &mut RowReader) -> Result<Self::Value, FromSqlError> {
+        row.read()
+    }
 }
 
 // The SQL to project a column is just its name.
-// (This might not be right---might need to include the
-// source/table name here.)
 impl<Table, Type> ToSql for Column<Table, Type> {
     type Sql = sstr;
 
     fn sql(&self) -> Self::Sql {
         self.name
     }
-}
\ No newline at end of file
+}
+
+// Once a query has more than one table, columns are written
+// along with the name of their table.
+impl<Table, Type> Render for Column<Table, Type>
+where
+    Table: Selectable {
+
+    fn render(&self, out: &mut Renderer) {
+        if out.qualifies_columns() {
+            out.push_identifier(Table::default().sql());
+            out.push(".");
+        }
+        out.push_identifier(self.name);
+    }
+}
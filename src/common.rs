@@ -3,11 +3,23 @@
 //! This module contains general traits and implementations that apply to other modules.
 //! Notably, macros to generate selection and display code for tuples are included here.
 
+use super::render::Render;
+
 /// A type representing a `str` with static lifetime.
 #[allow(non_camel_case_types)]
 pub type sstr = &'static str;
 
 /// Types that can be used to generate SQL.
+///
+/// For Rust values, this is their SQL literal:
+///
+/// ```
+/// # use tuna::builder::*;
+/// assert_eq!(42i64.sql().to_string(), "42");
+/// assert_eq!("o'brien".to_owned().sql(), "'o''brien'");
+/// assert_eq!(Some(true).sql().to_string(), "TRUE");
+/// assert_eq!(None::<f64>.sql().to_string(), "NULL");
+/// ```
 pub trait ToSql {
     /// The result type. This is something that can be displayed, but
     /// not necessarily a string. This makes it possible to return
@@ -20,10 +32,16 @@ pub trait ToSql {
 }
 
 /// Defines the types of things that can be projected from
-/// a source `Src` (most often a table).
-pub trait Projection<Src>: ToSql {
+/// a source `Src` (most often a table). `Idx` is where the projected
+/// columns are found within the source, which the compiler infers
+/// (see `Includes`).
+pub trait Projection<Src, Idx>: Render {
     /// The type that the projected thing gets in Rust.
     type Value;
+
+    /// Decodes the projected thing from a row returned by the database,
+    /// reading as many values from it as the projection selected.
+    fn decode(&self, row: &mut RowReader) -> Result<Self::Value, FromSqlError>;
 }
 
 /// A wrapper around an `Option` of a type `T` to allow for custom
@@ -55,6 +73,13 @@ impl<T: ToSql> std::fmt::Display for OptionT<T> {
 
 // impl ToSql for all possible rust data types.
 
+impl ToSql for bool {
+    type Sql = sstr;
+    fn sql(&self) -> Self::Sql {
+        if *self { "TRUE" } else { "FALSE" }
+    }
+}
+
 impl ToSql for i64 {
     type Sql = i64;
     fn sql(&self) -> Self::Sql {
@@ -62,11 +87,11 @@ impl ToSql for i64 {
     }
 }
 
+// Strings are quoted, with any quotes inside of them doubled up.
 impl ToSql for String {
     type Sql = String;
     fn sql(&self) -> Self::Sql {
-        //TODO: add sanitization
-        self.clone()
+        format!("'{}'", self.replace('\'', "''"))
     }
 }
 
@@ -77,6 +102,266 @@ impl ToSql for f64 {
     }
 }
 
+/// A value that is sent to the database alongside a query as a bound parameter,
+/// rather than being spliced into the SQL.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl ToSql for Value {
+    type Sql = String;
+    fn sql(&self) -> Self::Sql {
+        match self {
+            Value::Null => "NULL".to_owned(),
+            Value::Bool(a) => a.sql().to_owned(),
+            Value::Int(a) => a.sql().to_string(),
+            Value::Real(a) => a.sql().to_string(),
+            Value::Text(a) => a.sql(),
+            Value::Blob(a) => {
+                let hex: String = a.iter().map(|b| format!("{:02X}", b)).collect();
+                format!("X'{}'", hex)
+            }
+        }
+    }
+}
+
+/// Types whose values can be bound to a query parameter.
+pub trait ToValue {
+    /// Returns the parameter value for this thing.
+    fn to_value(&self) -> Value;
+}
+
+impl ToValue for bool {
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+impl ToValue for i32 {
+    fn to_value(&self) -> Value {
+        Value::Int(i64::from(*self))
+    }
+}
+
+impl ToValue for i64 {
+    fn to_value(&self) -> Value {
+        Value::Int(*self)
+    }
+}
+
+impl ToValue for f32 {
+    fn to_value(&self) -> Value {
+        Value::Real(f64::from(*self))
+    }
+}
+
+impl ToValue for f64 {
+    fn to_value(&self) -> Value {
+        Value::Real(*self)
+    }
+}
+
+impl ToValue for String {
+    fn to_value(&self) -> Value {
+        Value::Text(self.clone())
+    }
+}
+
+impl<T: ToValue> ToValue for Option<T> {
+    fn to_value(&self) -> Value {
+        match self {
+            Some(a) => a.to_value(),
+            None => Value::Null,
+        }
+    }
+}
+
+impl ToValue for Vec<u8> {
+    fn to_value(&self) -> Value {
+        Value::Blob(self.clone())
+    }
+}
+
+/// Types of text values, which can be matched against patterns with `LIKE`.
+pub trait TextValue: ToValue { }
+
+impl TextValue for String { }
+
+impl TextValue for Option<String> { }
+
+/// Types of values, along with the type of the ones that aren't null. The bounds
+/// of `between` are of this type, since a null bound would never match anything.
+pub trait NonNull: ToValue {
+    /// The type itself, or the type in the `Option` if it is one.
+    type Value: ToValue;
+}
+
+impl NonNull for bool { type Value = bool; }
+impl NonNull for i32 { type Value = i32; }
+impl NonNull for i64 { type Value = i64; }
+impl NonNull for f32 { type Value = f32; }
+impl NonNull for f64 { type Value = f64; }
+impl NonNull for String { type Value = String; }
+impl NonNull for Vec<u8> { type Value = Vec<u8>; }
+
+impl<T: ToValue> NonNull for Option<T> {
+    type Value = T;
+}
+
+/// The ways in which decoding a value returned by the database can fail.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FromSqlError {
+    /// The value was null, but the Rust type has no way to represent that.
+    UnexpectedNull,
+
+    /// The value can't be converted to the Rust type.
+    InvalidType {
+        expected: sstr,
+        found:    Value,
+    },
+
+    /// The row ran out of values before the projection was fully decoded.
+    /// This holds the zero based position of the missing value.
+    MissingColumn(usize),
+}
+
+impl std::fmt::Display for FromSqlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FromSqlError::UnexpectedNull => write!(f, "unexpected null value"),
+            FromSqlError::InvalidType { expected, found } =>
+                write!(f, "expected {}, found {:?}", expected, found),
+            FromSqlError::MissingColumn(position) =>
+                write!(f, "row has no value at position {}", position),
+        }
+    }
+}
+
+impl std::error::Error for FromSqlError { }
+
+/// Types that can be decoded from a value returned by the database.
+///
+/// ```
+/// # use tuna::builder::*;
+/// assert_eq!(i64::from_sql(Value::Int(3)), Ok(3));
+/// assert_eq!(Option::<i64>::from_sql(Value::Null), Ok(None));
+/// assert_eq!(i64::from_sql(Value::Null), Err(FromSqlError::UnexpectedNull));
+/// ```
+pub trait FromSql: Sized {
+    /// Converts the value into this type.
+    fn from_sql(value: Value) -> Result<Self, FromSqlError>;
+}
+
+/// Builds the error for a value that can't be decoded into the `expected` type.
+pub(crate) fn invalid<T>(expected: sstr, found: Value) -> Result<T, FromSqlError> {
+    match found {
+        Value::Null => Err(FromSqlError::UnexpectedNull),
+        found => Err(FromSqlError::InvalidType { expected, found }),
+    }
+}
+
+// Some databases (SQLite, MySQL) have no boolean type and store them as integers.
+impl FromSql for bool {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Bool(a) => Ok(a),
+            Value::Int(a) => Ok(a != 0),
+            found => invalid("bool", found),
+        }
+    }
+}
+
+impl FromSql for i32 {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Int(a) if a >= i64::from(i32::MIN) && a <= i64::from(i32::MAX) => Ok(a as i32),
+            found => invalid("i32", found),
+        }
+    }
+}
+
+impl FromSql for i64 {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Int(a) => Ok(a),
+            found => invalid("i64", found),
+        }
+    }
+}
+
+impl FromSql for f32 {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        f64::from_sql(value).map(|a| a as f32)
+    }
+}
+
+impl FromSql for f64 {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Real(a) => Ok(a),
+            Value::Int(a) => Ok(a as f64),
+            found => invalid("f64", found),
+        }
+    }
+}
+
+impl FromSql for String {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Text(a) => Ok(a),
+            found => invalid("String", found),
+        }
+    }
+}
+
+impl FromSql for Vec<u8> {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Blob(a) => Ok(a),
+            found => invalid("Vec<u8>", found),
+        }
+    }
+}
+
+impl<T: FromSql> FromSql for Option<T> {
+    fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+        match value {
+            Value::Null => Ok(None),
+            value => T::from_sql(value).map(Some),
+        }
+    }
+}
+
+/// Reads the values of a row returned by the database in order, so that a
+/// projection can decode each of its parts by position.
+pub struct RowReader {
+    values:   std::vec::IntoIter<Value>,
+    position: usize,
+}
+
+impl RowReader {
+    /// Starts reading the given row from its first value.
+    pub fn new(row: Vec<Value>) -> Self {
+        RowReader {
+            values:   row.into_iter(),
+            position: 0,
+        }
+    }
+
+    /// Decodes the next value in the row.
+    pub fn read<T: FromSql>(&mut self) -> Result<T, FromSqlError> {
+        let value = self.values.next().ok_or(FromSqlError::MissingColumn(self.position))?;
+        self.position += 1;
+        T::from_sql(value)
+    }
+}
+
 /// This module represents something that can be `join`ed together to be displayed.
 pub mod join {
     use std::fmt::{Display, Formatter, Result};
@@ -101,11 +386,19 @@ pub mod join {
 
 /// Allows selection of tuples like (A. B), (A, B, C), (A, B, C, D) ...
 macro_rules! impl_projection_for_tuple {
-    ($($tv:ident),* $(,)?) => {
-        impl<Src, $($tv),*> $crate::common::Projection<Src> for ($($tv,)*)
+    ($(($tv:ident $ti:ident)),* $(,)?) => {
+        impl<Src, $($tv, $ti),*> $crate::common::Projection<Src, ($($ti,)*)> for ($($tv,)*)
         where
-            $( $tv: $crate::common::Projection<Src>, )* {
-                type Value = ( $($tv::Value),* );
+            $( $tv: $crate::common::Projection<Src, $ti>, )* {
+                type Value = ( $(<$tv as $crate::common::Projection<Src, $ti>>::Value),* );
+
+                #[allow(non_snake_case)]
+                fn decode(&self, row: &mut $crate::common::RowReader)
+                    -> Result<Self::Value, $crate::common::FromSqlError> {
+
+                    let ($($tv,)*) = self;
+                    Ok(( $($tv.decode(row)?),* ))
+                }
         }
 
         impl<$($tv),*> $crate::common::ToSql for ($($tv,)*)
@@ -123,25 +416,44 @@ macro_rules! impl_projection_for_tuple {
                 }
             }
         }
+
+        impl<$($tv),*> $crate::render::Render for ($($tv,)*)
+        where
+            $( $tv: $crate::render::Render, )* {
+
+            #[allow(non_snake_case)]
+            fn render(&self, out: &mut $crate::render::Renderer) {
+                let ($($tv,)*) = self;
+                let mut sep = "";
+                $(
+                    out.push(sep);
+                    out.render($tv);
+                    sep = ", ";
+                )*
+                let _ = sep;
+            }
+        }
     };
 }
 
 macro_rules! apply_macro_for_tuples {
-    ($op:ident!{ $($now:ident)* ;}) => {
+    ($op:ident!{ [$($now:tt)*] }) => {
         $op!{$($now),*}
     };
-    ($op:ident!{$($now:ident)*; $next:ident $($later:ident)*}) => {
+    ($op:ident!{ [$($now:tt)*] $next:tt $($later:tt)* }) => {
         $op!{$($now),*}
-        apply_macro_for_tuples!{$op!{$($now)* $next; $($later)*}}
+        apply_macro_for_tuples!{$op!{[$($now)* $next] $($later)*}}
     };
 }
 
 apply_macro_for_tuples! {
     impl_projection_for_tuple! {
-        // starts with these:
-        A B;
+        // starts with these (each type along with its index):
+        [(A IA) (B IB)]
         // then adds each of these in order:
-        C D E F G H I J K L M N O P Q R S T U V W X Y Z
+        (C IC) (D ID) (E IE) (F IF) (G IG) (H IH) (I II) (J IJ) (K IK) (L IL) (M IM)
+        (N IN) (O IO) (P IP) (Q IQ) (R IR) (S IS) (T IT) (U IU) (V IV) (W IW) (X IX)
+        (Y IY) (Z IZ)
     }
 }
 
@@ -170,7 +482,7 @@ macro_rules! impl_display_join_for_tuple {
 
 apply_macro_for_tuples! {
     impl_display_join_for_tuple! {
-        A B C;
+        [A B C]
         D E F G H I J K L M N O P Q R S T U V W X Y Z
     }
 }
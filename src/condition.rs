@@ -3,22 +3,71 @@
 //! This module contains definitions and implementations related to SQL conditions.
 //! That is, things that go after the `WHERE` clause.
 
-use super::common::*;
+use super::{
+  builder::Subquery,
+  column::Column,
+  common::*,
+  render::{Render, Renderer, Precedence},
+  source::{Includes, Here}
+};
 
 /// A trait to represent a condition to put in a SQL `WHERE` clause.
-/// The parameter is the source of the data for the condition, which
+/// The parameter `Src` is the source of the data for the condition, which
 /// prevents us from referring to columns that don't exist in the
-/// given query's source table.
-pub trait Condition<Src>: ToSql {
-    /// Chains two conditions from the same souce together using the SQL `AND` clause.
-    fn and<Other: Condition<Src>>(self, other: Other) -> Both<Self, Other>
+/// given query's source table. `Idx` is where those columns are found
+/// in the source, which the compiler infers (see `Includes`).
+pub trait Condition<Src, Idx>: Predicate { }
+
+/// A trait to represent a condition to put in a SQL `HAVING` clause, which filters
+/// the groups of a grouped query. Every `Condition` is one, as are comparisons of
+/// aggregates, which aren't `Condition`s since they can't be checked row by row:
+///
+/// ```compile_fail
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "orders")]
+/// # struct Orders {
+/// #     user_id: Int,
+/// #     total:   Double,
+/// # }
+/// let query = ORDERS.select(USER_ID).filter(count(TOTAL).greater_than(1)).finish(Sqlite);
+/// ```
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "orders")]
+/// # struct Orders {
+/// #     user_id: Int,
+/// #     total:   Double,
+/// # }
+/// let query = ORDERS
+///     .select(USER_ID)
+///     .group_by(USER_ID)
+///     .having(count(TOTAL).greater_than(1).and(USER_ID.not_equals(0)))
+///     .finish(Sqlite);
+///
+/// assert_eq!(query.sql, r#"SELECT "user_id" FROM "orders" GROUP BY "user_id" HAVING COUNT("orders"."total") > ? AND "orders"."user_id" <> ?"#);
+/// ```
+///
+/// As with the columns selected alongside aggregates, the compiler can't tell whether a
+/// column that isn't aggregated is one of those grouped by. A condition on any other column
+/// of the source, like `TOTAL.greater_than(1.0)` above, is still accepted, and is left for
+/// the database to reject.
+pub trait GroupCondition<Src, Idx>: Predicate { }
+
+/// Conditions that can be combined with others. Whether the result only refers to
+/// columns of the query's source is checked once it is used, by `Condition` or `GroupCondition`.
+pub trait Predicate: Render {
+    /// Chains two conditions together using the SQL `AND` clause.
+    fn and<Other: Predicate>(self, other: Other) -> Both<Self, Other>
     where
         Self: Sized {
         Both(self, other)
     }
 
-    /// Chains two conditions from the same source together using the SQL `OR` clause.
-    fn or<Other: Condition<Src>>(self, other: Other) -> Either<Self, Other>
+    /// Chains two conditions together using the SQL `OR` clause.
+    fn or<Other: Predicate>(self, other: Other) -> Either<Self, Other>
     where
         Self: Sized {
         Either(self, other)
@@ -32,7 +81,90 @@ pub trait Condition<Src>: ToSql {
     }
 }
 
+/// Something that a column or other projection whose values are of type `Type` can be
+/// compared against. This is either a value of that type, which is bound as a parameter,
+/// or another column of the same type:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     created_at: DateTime,
+/// #     updated_at: Nullable<DateTime>,
+/// #     deleted_at: Nullable<DateTime>,
+/// # }
+/// let new_year: DateTimeValue = "2019-01-01 00:00:00".parse().unwrap();
+/// let mut out = Renderer::new(&Postgres);
+/// out.render(&UPDATED_AT.greater_than(Some(new_year))
+///                       .or(DELETED_AT.equals(UPDATED_AT)));
+///
+/// assert_eq!(out.into_parts().0,
+///            r#""users"."updated_at" > $1 OR "users"."deleted_at" = "users"."updated_at""#);
+/// ```
+///
+/// Columns of different types, like `CREATED_AT` and `UPDATED_AT` above (`DateTimeValue`
+/// and `Option<DateTimeValue>`), can't be compared. A column can be compared against a column of
+/// another table as long as both tables are part of the query's source.
+pub trait Operand<Type> {
+    /// Writes the operand as the right hand side of a comparison.
+    fn render_operand(&self, out: &mut Renderer);
+
+    /// Whether the operand is a null value, which can only be compared
+    /// against with `IS NULL`.
+    fn is_null(&self) -> bool {
+        false
+    }
+
+    /// How tightly the operand binds, as for `Render`.
+    fn precedence(&self) -> Precedence {
+        Precedence::Atom
+    }
+}
+
+/// Operands that can be used in a condition on `Src`: values, which don't need
+/// anything from the source, and columns of any of its tables.
+pub trait InScope<Src, Idx> { }
+
+impl<Type: ToValue> Operand<Type> for Type {
+    fn render_operand(&self, out: &mut Renderer) {
+        out.push_param(self.to_value());
+    }
+
+    fn is_null(&self) -> bool {
+        self.to_value() == Value::Null
+    }
+}
+
+impl<Src, Type: ToValue> InScope<Src, Here> for Type { }
+
 /// A condition representing a pair of conditions grouped together by `AND`.
+///
+/// Conditions are parenthesized when rendered wherever SQL's operator precedence
+/// would otherwise group them differently than they were built:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     id: Int,
+/// # }
+/// fn render<I, C: Condition<Users, I>>(condition: C) -> String {
+///     let mut out = Renderer::new(&Sqlite);
+///     out.render(&condition);
+///     out.into_parts().0.replace(r#""users"."id""#, "id")
+/// }
+///
+/// assert_eq!(render(ID.equals(1).or(ID.equals(2)).and(ID.equals(3))),
+///            "(id = ? OR id = ?) AND id = ?");
+/// assert_eq!(render(ID.equals(1).or(ID.equals(2).and(ID.equals(3)))),
+///            "id = ? OR id = ? AND id = ?");
+/// assert_eq!(render(ID.equals(1).and(ID.equals(2).or(ID.equals(3))).not()),
+///            "NOT (id = ? AND (id = ? OR id = ?))");
+/// assert_eq!(render(ID.equals(1).not().and(ID.null().not())),
+///            "NOT id = ? AND NOT id IS NULL");
+/// ```
 pub struct Both<A, B>(A, B);
 
 /// A condition representing a pair of conditions grouped together by `OR`.
@@ -41,18 +173,36 @@ pub struct Either<A, B>(A, B);
 /// A condition prefixed with `NOT`.
 pub struct Not<A>(A);
 
-/// A condition to check if the column or other projection is equal to some other value.
-pub struct Equals<Src, Prj, Type> {
+/// A condition to check if the column or other projection is equal to some other value or column.
+///
+/// Comparing against a null value with `=` or `<>` is never true in SQL, so when the
+/// value is `None` these are rendered as `IS NULL` and `IS NOT NULL` instead:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     login_count: Nullable<Int>,
+/// # }
+/// let mut out = Renderer::new(&MySql);
+/// out.render(&LOGIN_COUNT.equals(None).or(LOGIN_COUNT.not_equals(Some(0))));
+///
+/// assert_eq!(out.into_parts(),
+///            ("`users`.`login_count` IS NULL OR `users`.`login_count` <> ?".to_owned(),
+///             vec![Value::Int(0)]));
+/// ```
+pub struct Equals<Src, Prj, Rhs> {
     pub source:     Src,
     pub projection: Prj,
-    pub value:      Type,
+    pub other:      Rhs,
 }
 
 /// A condition to check if the column or other projection is not equal to some other value.
-pub struct NotEq<Src, Prj, Type> {
+pub struct NotEq<Src, Prj, Rhs> {
     pub source:     Src,
     pub projection: Prj,
-    pub value:      Type
+    pub other:      Rhs,
 }
 
 /// A condition to check if the column or other projection is null.
@@ -68,281 +218,817 @@ pub struct IsNotNull<Src, Prj> {
 }
 
 /// A condition to check if the column or other projection is less than some other value.
-pub struct Less<Src, Prj, Type> {
+pub struct Less<Src, Prj, Rhs> {
     pub source:     Src,
     pub projection: Prj,
-    pub value:      Type,
+    pub other:      Rhs,
 }
 
 /// A condition to check if the column or other projection is greater than some other value.
-pub struct Greater<Src, Prj, Type> {
+pub struct Greater<Src, Prj, Rhs> {
     pub source:     Src,
     pub projection: Prj,
-    pub value:      Type,
+    pub other:      Rhs,
 }
 
 /// A condition to check if the column or other projection is less than or equal to some other value.
-pub struct Leq<Src, Prj, Type> {
+pub struct Leq<Src, Prj, Rhs> {
     pub source:     Src,
     pub projection: Prj,
-    pub value:      Type,
+    pub other:      Rhs,
 }
 
 /// A condition to check if the column or other projection is greater than or equal to some other value.
-pub struct Geq<Src, Prj, Type> {
+pub struct Geq<Src, Prj, Rhs> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub other:      Rhs,
+}
+
+/// A condition to check if the column or other projection is equal to any of a list of values.
+///
+/// Each value is bound as its own parameter. As with `Equals`, a `None` in the list is
+/// checked with `IS NULL`, and an empty list is never met (rather than rendering the
+/// invalid `IN ()`):
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     id:          Int,
+/// #     login_count: Nullable<Int>,
+/// # }
+/// fn render<I, C: Condition<Users, I>>(condition: C) -> String {
+///     let mut out = Renderer::new(&Postgres);
+///     out.render(&condition);
+///     out.into_parts().0.replace(r#""users"."#, "")
+/// }
+///
+/// assert_eq!(render(ID.is_in(vec![1, 2, 3])), r#""id" IN ($1, $2, $3)"#);
+/// assert_eq!(render(ID.not_in(1..3)), r#""id" NOT IN ($1, $2)"#);
+/// assert_eq!(render(ID.is_in(vec![]).or(ID.not_in(vec![]))), "FALSE OR TRUE");
+/// assert_eq!(render(LOGIN_COUNT.is_in(vec![Some(0), None]).and(ID.equals(1))),
+///            r#"("login_count" IN ($1) OR "login_count" IS NULL) AND "id" = $2"#);
+/// assert_eq!(render(LOGIN_COUNT.not_in(vec![Some(0), None])),
+///            r#""login_count" NOT IN ($1) AND "login_count" IS NOT NULL"#);
+/// ```
+pub struct In<Src, Prj, Type> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub values:     Vec<Type>,
+}
+
+/// A condition to check if the column or other projection is equal to none of a list of values.
+/// An empty list is always met.
+pub struct NotIn<Src, Prj, Type> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub values:     Vec<Type>,
+}
+
+/// A condition to check if the column or other projection is within a range of values,
+/// including both ends.
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     id: Int,
+/// # }
+/// let mut out = Renderer::new(&Postgres);
+/// out.render(&ID.between(10, 20).and(ID.not_between(12, 14)));
+///
+/// assert_eq!(out.into_parts(),
+///            (r#""users"."id" BETWEEN $1 AND $2 AND "users"."id" NOT BETWEEN $3 AND $4"#.to_owned(),
+///             vec![Value::Int(10), Value::Int(20), Value::Int(12), Value::Int(14)]));
+/// ```
+///
+/// A null bound would never match anything, so the bounds of a nullable column
+/// are its values that aren't null:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     login_count: Nullable<Int>,
+/// # }
+/// let mut out = Renderer::new(&Postgres);
+/// out.render(&LOGIN_COUNT.between(1, 3));
+/// assert_eq!(out.into_parts().0, r#""users"."login_count" BETWEEN $1 AND $2"#);
+/// ```
+///
+/// ```compile_fail
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     login_count: Nullable<Int>,
+/// # }
+/// let condition = LOGIN_COUNT.between(None, Some(3));
+/// ```
+pub struct Between<Src, Prj, Type> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub low:        Type,
+    pub high:       Type,
+}
+
+/// A condition to check if the column or other projection is outside a range of values.
+pub struct NotBetween<Src, Prj, Type> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub low:        Type,
+    pub high:       Type,
+}
+
+/// A condition to check if the column or other projection matches a `LIKE` pattern, where
+/// `%` matches any run of characters and `_` matches any one character.
+///
+/// When the pattern is built from user input by `starts_with`, `ends_with` or `contains`,
+/// the input's own `%` and `_` are escaped so that they match only themselves:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     name: Text,
+/// # }
+/// let mut out = Renderer::new(&Postgres);
+/// out.render(&NAME.starts_with("100%"));
+///
+/// assert_eq!(out.into_parts(),
+///            (r#""users"."name" LIKE $1 ESCAPE '!'"#.to_owned(),
+///             vec![Value::Text("100!%%".to_owned())]));
+///
+/// // Only Postgres has `ILIKE`; elsewhere both sides are lowercased.
+/// let mut out = Renderer::new(&Sqlite);
+/// out.render(&NAME.ilike("tuna%"));
+///
+/// assert_eq!(out.into_parts().0, r#"LOWER("users"."name") LIKE LOWER(?)"#);
+/// ```
+pub struct Like<Src, Prj> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub pattern:    String,
+    pub escape:     Option<char>,
+}
+
+/// A condition to check if the column or other projection doesn't match a `LIKE` pattern.
+pub struct NotLike<Src, Prj> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub pattern:    String,
+    pub escape:     Option<char>,
+}
+
+/// A condition to check if the column or other projection matches a `LIKE` pattern,
+/// ignoring case.
+pub struct ILike<Src, Prj> {
+    pub source:     Src,
+    pub projection: Prj,
+    pub pattern:    String,
+    pub escape:     Option<char>,
+}
+
+/// A condition to check if the column or other projection is equal to any of the values
+/// returned by a subquery.
+///
+/// The subquery is a `SELECT` statement that hasn't been finished yet, and is rendered in the
+/// dialect that the query it is used in is finished for. It can't refer to the columns of that
+/// query, and its parameters are numbered along with the ones around it:
+///
+/// ```
+/// # use tuna::builder::*;
+/// #[derive(Tuna)]
+/// #[tuna(table = "users")]
+/// struct Users {
+///     id:   Int,
+///     name: Text,
+/// }
+///
+/// #[derive(Tuna)]
+/// #[tuna(table = "orders")]
+/// struct Orders {
+///     user_id: Int,
+///     total:   Double,
+/// }
+///
+/// let big_spenders = || ORDERS.select(USER_ID).filter(TOTAL.greater_than(100.0));
+///
+/// let query = USERS
+///     .select(NAME)
+///     .filter(NAME.starts_with("t").and(ID.in_query(big_spenders())))
+///     .finish(Postgres);
+///
+/// assert_eq!(query.sql, r#"SELECT "name" FROM "users" WHERE "users"."name" LIKE $1 ESCAPE '!' AND "users"."id" IN (SELECT "user_id" FROM "orders" WHERE "orders"."total" > $2)"#);
+/// assert_eq!(query.params, vec![Value::Text("t%".to_owned()), Value::Real(100.0)]);
+///
+/// # #[cfg(feature = "sqlite")] {
+/// let conn = SqliteConnection::open_in_memory()?;
+/// conn.execute_batch("CREATE TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL);
+///                     CREATE TABLE orders (user_id INTEGER NOT NULL, total REAL NOT NULL);
+///                     INSERT INTO users VALUES (1, 'tuna'), (2, 'trout');
+///                     INSERT INTO orders VALUES (1, 250.0), (2, 20.0);")?;
+///
+/// let query = USERS.select(NAME).filter(ID.in_query(big_spenders())).finish(Sqlite);
+/// assert_eq!(conn.fetch_all(&query)?, vec!["tuna".to_owned()]);
+///
+/// // `exists` only checks that there is some big spender at all, so it keeps every user.
+/// let query = USERS.select(NAME).filter(exists(big_spenders())).finish(Sqlite);
+/// assert_eq!(conn.fetch_all(&query)?.len(), 2);
+/// # }
+/// # Ok::<(), tuna::connection::Error>(())
+/// ```
+///
+/// The subquery has to select a single column of the same type:
+///
+/// ```compile_fail
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # struct Users {
+/// #     id:   Int,
+/// #     name: Text,
+/// # }
+/// let query = USERS.select(NAME).filter(ID.in_query(USERS.select(NAME)));
+/// ```
+///
+/// Nor can it use anything that only some dialects support, since it isn't known yet which
+/// one it will be rendered in:
+///
+/// ```compile_fail
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # struct Users {
+/// #     id:   Int,
+/// #     name: Text,
+/// # }
+/// let firsts = USERS.select(ID).distinct_on(NAME);
+/// let query = USERS.select(NAME).filter(ID.in_query(firsts)).finish(Postgres);
+/// ```
+pub struct InQuery<Src, Prj, Sub> {
     pub source:     Src,
     pub projection: Prj,
-    pub value:      Type,
+    pub query:      Sub,
+}
+
+/// A condition to check if a subquery returns any rows.
+pub struct Exists<Sub> {
+    pub query: Sub,
+}
+
+/// A condition to check if a subquery returns no rows.
+pub struct NotExists<Sub> {
+    pub query: Sub,
+}
+
+/// Checks to see if a subquery returns any rows.
+///
+/// The subquery can't refer to the columns of the query it is used in, so it is
+/// the same for every row: either all of them are kept or none are. To keep only
+/// the rows that have related rows in another table, like the users with an
+/// order, use `in_query` on the column that relates them instead.
+pub fn exists<Sub: Subquery>(query: Sub) -> Exists<Sub> {
+    Exists { query }
 }
 
-impl<A, B> ToSql for Both<A, B>
+/// Checks to see if a subquery returns no rows.
+///
+/// Like `exists`, the subquery can't refer to the query it is used in. To keep
+/// the rows that have no related rows in another table, use `in_query(...).not()`.
+pub fn not_exists<Sub: Subquery>(query: Sub) -> NotExists<Sub> {
+    NotExists { query }
+}
+
+impl<A, B> Render for Both<A, B>
 where
-    A: ToSql,
-    B: ToSql {
+    A: Render,
+    B: Render {
 
-    type Sql = join::Join<sstr, (A::Sql, sstr, B::Sql)>;
+    fn render(&self, out: &mut Renderer) {
+        out.render_operand(&self.0, Precedence::And);
+        out.push(" AND ");
+        out.render_operand(&self.1, Precedence::And);
+    }
 
-    fn sql(&self) -> Self::Sql {
-        join::Join{
-            sep: "",
-            tup: (self.0.sql(),
-                  " AND ",
-                  self.1.sql())
-        }
+    fn precedence(&self) -> Precedence {
+        Precedence::And
     }
 }
 
-impl<A, B> ToSql for Either<A, B>
+impl<A, B> Render for Either<A, B>
 where
-    A: ToSql,
-    B: ToSql {
+    A: Render,
+    B: Render {
 
-    type Sql = join::Join<sstr, (A::Sql, sstr, B::Sql)>;
+    fn render(&self, out: &mut Renderer) {
+        out.render_operand(&self.0, Precedence::Or);
+        out.push(" OR ");
+        out.render_operand(&self.1, Precedence::Or);
+    }
 
-    fn sql(&self) -> Self::Sql {
-        join::Join{
-            sep: "",
-            tup: (self.0.sql(),
-                  " OR ",
-                  self.1.sql())
-        }
+    fn precedence(&self) -> Precedence {
+        Precedence::Or
     }
 }
 
-impl<A> ToSql for Not<A>
+impl<A> Render for Not<A>
 where
-    A: ToSql {
+    A: Render {
 
-    type Sql = join::Join<sstr, (sstr, A::Sql)>;
+    fn render(&self, out: &mut Renderer) {
+        out.push("NOT ");
+        out.render_operand(&self.0, Precedence::Not);
+    }
 
-    fn sql(&self) -> Self::Sql {
-        join::Join{
-            sep: "",
-            tup: ("NOT ",
-                  self.0.sql())
-        }
+    fn precedence(&self) -> Precedence {
+        Precedence::Not
     }
 }
 
-impl<Src, Prj, Type> ToSql for Equals<Src, Prj, Type>
+impl<Src, Prj, Rhs> Render for Equals<Src, Prj, Rhs>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> {
+    Prj: Projection<Src, Here>,
+    Rhs: Operand<Prj::Value> {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        if self.other.is_null() {
+            out.push(" IS NULL");
+        } else {
+            out.push(" = ");
+            self.other.render_operand(out);
+        }
+    }
+}
 
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr, Type::Sql, sstr)>;
+impl<Src, Prj, Rhs> Render for NotEq<Src, Prj, Rhs>
+where
+    Prj: Projection<Src, Here>,
+    Rhs: Operand<Prj::Value> {
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " = '",
-                  self.value.sql(),      "'"),
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        if self.other.is_null() {
+            out.push(" IS NOT NULL");
+        } else {
+            out.push(" <> ");
+            self.other.render_operand(out);
         }
     }
 }
 
-impl<Src, Prj, Type> ToSql for NotEq<Src, Prj, Type>
+impl<Src, Prj> Render for IsNull<Src, Prj>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> {
+    Prj: Render {
 
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr, Type::Sql, sstr)>;
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" IS NULL");
+    }
+}
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " <> '",
-                  self.value.sql(),      "'"),
-        }
+impl<Src, Prj> Render for IsNotNull<Src, Prj>
+where
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" IS NOT NULL");
     }
 }
 
-impl<Src, Prj> ToSql for IsNull<Src, Prj>
+impl<Src, Prj, Rhs> Render for Less<Src, Prj, Rhs>
 where
-    Src: ToSql,
-    Prj: Projection<Src>,
-{
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr)>;
+    Prj: Projection<Src, Here>,
+    Rhs: Operand<Prj::Value> {
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " IS NULL"),
-        }
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" < ");
+        self.other.render_operand(out);
     }
 }
 
-impl<Src, Prj> ToSql for IsNotNull<Src, Prj>
+impl<Src, Prj, Rhs> Render for Greater<Src, Prj, Rhs>
 where
-    Src: ToSql,
-    Prj: Projection<Src>,
-{
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr)>;
+    Prj: Projection<Src, Here>,
+    Rhs: Operand<Prj::Value> {
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " IS NOT NULL"),
-        }
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" > ");
+        self.other.render_operand(out);
     }
 }
 
-impl<Src, Prj, Type> ToSql for Less<Src, Prj, Type>
+impl<Src, Prj, Rhs> Render for Leq<Src, Prj, Rhs>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> {
+    Prj: Projection<Src, Here>,
+    Rhs: Operand<Prj::Value> {
 
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr, Type::Sql, sstr)>;
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" <= ");
+        self.other.render_operand(out);
+    }
+}
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " < '",
-                  self.value.sql(),      "'"),
-        }
+impl<Src, Prj, Rhs> Render for Geq<Src, Prj, Rhs>
+where
+    Prj: Projection<Src, Here>,
+    Rhs: Operand<Prj::Value> {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" >= ");
+        self.other.render_operand(out);
     }
 }
 
-impl<Src, Prj, Type> ToSql for Greater<Src, Prj, Type>
+impl<Src, Prj, Type> Render for In<Src, Prj, Type>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> {
+    Type: ToValue,
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        render_membership(out, &self.projection, &self.values, false);
+    }
 
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr, Type::Sql, sstr)>;
+    fn precedence(&self) -> Precedence {
+        membership_precedence(&self.values, Precedence::Or)
+    }
+}
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " > '",
-                  self.value.sql(),      "'"),
-        }
+impl<Src, Prj, Type> Render for NotIn<Src, Prj, Type>
+where
+    Type: ToValue,
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        render_membership(out, &self.projection, &self.values, true);
+    }
+
+    fn precedence(&self) -> Precedence {
+        membership_precedence(&self.values, Precedence::And)
     }
 }
 
-impl<Src, Prj, Type> ToSql for Leq<Src, Prj, Type>
+impl<Src, Prj, Type> Render for Between<Src, Prj, Type>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> {
+    Type: ToValue,
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" BETWEEN ");
+        out.push_param(self.low.to_value());
+        out.push(" AND ");
+        out.push_param(self.high.to_value());
+    }
+}
+
+impl<Src, Prj, Type> Render for NotBetween<Src, Prj, Type>
+where
+    Type: ToValue,
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" NOT BETWEEN ");
+        out.push_param(self.low.to_value());
+        out.push(" AND ");
+        out.push_param(self.high.to_value());
+    }
+}
+
+impl<Src, Prj> Render for Like<Src, Prj>
+where
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" LIKE ");
+        render_pattern(out, &self.pattern, self.escape);
+    }
+}
+
+impl<Src, Prj> Render for NotLike<Src, Prj>
+where
+    Prj: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" NOT LIKE ");
+        render_pattern(out, &self.pattern, self.escape);
+    }
+}
 
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr, Type::Sql, sstr)>;
+impl<Src, Prj> Render for ILike<Src, Prj>
+where
+    Prj: Render {
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " <= '",
-                  self.value.sql(),      "'"),
+    fn render(&self, out: &mut Renderer) {
+        if out.dialect().supports_ilike() {
+            out.render_qualified(&self.projection);
+            out.push(" ILIKE ");
+            render_pattern(out, &self.pattern, self.escape);
+        } else {
+            out.push("LOWER(");
+            out.render_qualified(&self.projection);
+            out.push(") LIKE LOWER(");
+            out.push_param(Value::Text(self.pattern.clone()));
+            out.push(")");
+            render_escape(out, self.escape);
         }
     }
 }
 
-impl<Src, Prj, Type> ToSql for Geq<Src, Prj, Type>
+impl<Src, Prj, Sub> Render for InQuery<Src, Prj, Sub>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> {
+    Prj: Render,
+    Sub: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render_qualified(&self.projection);
+        out.push(" IN ");
+        out.render_subquery(&self.query);
+    }
+}
 
-    type Sql = join::Join<sstr, (Src::Sql, sstr, Prj::Sql, sstr, Type::Sql, sstr)>;
+impl<Sub: Render> Render for Exists<Sub> {
+    fn render(&self, out: &mut Renderer) {
+        out.push("EXISTS ");
+        out.render_subquery(&self.query);
+    }
+}
+
+impl<Sub: Render> Render for NotExists<Sub> {
+    fn render(&self, out: &mut Renderer) {
+        out.push("NOT EXISTS ");
+        out.render_subquery(&self.query);
+    }
+}
+
+/// Binds a `LIKE` pattern, followed by its escape character if it has one.
+fn render_pattern(out: &mut Renderer, pattern: &str, escape: Option<char>) {
+    out.push_param(Value::Text(pattern.to_owned()));
+    render_escape(out, escape);
+}
+
+fn render_escape(out: &mut Renderer, escape: Option<char>) {
+    if let Some(escape) = escape {
+        out.push(" ESCAPE ");
+        out.push(escape.to_string().sql());
+    }
+}
+
+/// Renders `IN` or `NOT IN`, splitting nulls out of the list since SQL never
+/// finds them in it.
+fn render_membership<Prj, Type>(out: &mut Renderer, projection: &Prj, values: &[Type], negated: bool)
+where
+    Type: ToValue,
+    Prj: Render {
+
+    let (nulls, values): (Vec<Value>, Vec<Value>) = values
+        .iter()
+        .map(ToValue::to_value)
+        .partition(|value| *value == Value::Null);
+
+    if nulls.is_empty() && values.is_empty() {
+        out.push(out.dialect().boolean(negated));
+        return;
+    }
+
+    if !values.is_empty() {
+        out.render_qualified(projection);
+        out.push(if negated { " NOT IN (" } else { " IN (" });
+        for (i, value) in values.into_iter().enumerate() {
+            if i > 0 {
+                out.push(", ");
+            }
+            out.push_param(value);
+        }
+        out.push(")");
 
-    fn sql(&self) -> Self::Sql {
-        join::Join {
-            sep: "",
-            tup: (self.source.sql(),     ".",
-                  self.projection.sql(), " >= '",
-                  self.value.sql(),      "'"),
+        if nulls.is_empty() {
+            return;
         }
+        out.push(if negated { " AND " } else { " OR " });
+    }
+
+    out.render_qualified(projection);
+    out.push(if negated { " IS NOT NULL" } else { " IS NULL" });
+}
+
+/// A membership check joins its two halves with `combined` when the list has
+/// both nulls and other values, and is a single atom otherwise.
+fn membership_precedence<Type: ToValue>(values: &[Type], combined: Precedence) -> Precedence {
+    let nulls = values.iter().filter(|value| value.to_value() == Value::Null).count();
+
+    if nulls > 0 && nulls < values.len() {
+        combined
+    } else {
+        Precedence::Atom
     }
 }
 
-impl <Src, A, B> Condition<Src> for Both<A, B>
+impl<A: Predicate, B: Predicate> Predicate for Both<A, B> { }
+impl<A: Predicate, B: Predicate> Predicate for Either<A, B> { }
+impl<A: Predicate> Predicate for Not<A> { }
+impl<Src, Prj, Rhs> Predicate for Equals<Src, Prj, Rhs> where Self: Render { }
+impl<Src, Prj, Rhs> Predicate for NotEq<Src, Prj, Rhs> where Self: Render { }
+impl<Src, Prj> Predicate for IsNull<Src, Prj> where Self: Render { }
+impl<Src, Prj> Predicate for IsNotNull<Src, Prj> where Self: Render { }
+impl<Src, Prj, Rhs> Predicate for Less<Src, Prj, Rhs> where Self: Render { }
+impl<Src, Prj, Rhs> Predicate for Greater<Src, Prj, Rhs> where Self: Render { }
+impl<Src, Prj, Rhs> Predicate for Leq<Src, Prj, Rhs> where Self: Render { }
+impl<Src, Prj, Rhs> Predicate for Geq<Src, Prj, Rhs> where Self: Render { }
+impl<Src, Prj, Type> Predicate for In<Src, Prj, Type> where Self: Render { }
+impl<Src, Prj, Type> Predicate for NotIn<Src, Prj, Type> where Self: Render { }
+impl<Src, Prj, Type> Predicate for Between<Src, Prj, Type> where Self: Render { }
+impl<Src, Prj, Type> Predicate for NotBetween<Src, Prj, Type> where Self: Render { }
+impl<Src, Prj> Predicate for Like<Src, Prj> where Self: Render { }
+impl<Src, Prj> Predicate for NotLike<Src, Prj> where Self: Render { }
+impl<Src, Prj> Predicate for ILike<Src, Prj> where Self: Render { }
+impl<Src, Prj, Sub> Predicate for InQuery<Src, Prj, Sub> where Self: Render { }
+impl<Sub> Predicate for Exists<Sub> where Self: Render { }
+impl<Sub> Predicate for NotExists<Sub> where Self: Render { }
+
+impl<Src, A, B, IA, IB> Condition<Src, (IA, IB)> for Both<A, B>
+where
+    A: Condition<Src, IA>,
+    B: Condition<Src, IB> { }
+
+impl<Src, A, B, IA, IB> Condition<Src, (IA, IB)> for Either<A, B>
+where
+    A: Condition<Src, IA>,
+    B: Condition<Src, IB> { }
+
+impl<Src, A, Idx> Condition<Src, Idx> for Not<A>
+where
+    A: Condition<Src, Idx> { }
+
+// Only columns can be compared row by row. Comparisons of aggregates
+// are `GroupCondition`s instead.
+impl<Src, Table, Type, Rhs, I, J> Condition<Src, (I, J)> for Equals<Table, Column<Table, Type>, Rhs>
+where
+    Src: Includes<Table, I>,
+    Column<Table, Type>: Projection<Table, Here, Value = Type>,
+    Rhs: Operand<Type> + InScope<Src, J> { }
+
+impl<Src, Table, Type, Rhs, I, J> Condition<Src, (I, J)> for NotEq<Table, Column<Table, Type>, Rhs>
+where
+    Src: Includes<Table, I>,
+    Column<Table, Type>: Projection<Table, Here, Value = Type>,
+    Rhs: Operand<Type> + InScope<Src, J> { }
+
+impl<Src, Table, Type, Rhs, I, J> Condition<Src, (I, J)> for Less<Table, Column<Table, Type>, Rhs>
+where
+    Src: Includes<Table, I>,
+    Column<Table, Type>: Projection<Table, Here, Value = Type>,
+    Rhs: Operand<Type> + InScope<Src, J> { }
+
+impl<Src, Table, Type, Rhs, I, J> Condition<Src, (I, J)> for Greater<Table, Column<Table, Type>, Rhs>
+where
+    Src: Includes<Table, I>,
+    Column<Table, Type>: Projection<Table, Here, Value = Type>,
+    Rhs: Operand<Type> + InScope<Src, J> { }
+
+impl<Src, Table, Type, Rhs, I, J> Condition<Src, (I, J)> for Leq<Table, Column<Table, Type>, Rhs>
+where
+    Src: Includes<Table, I>,
+    Column<Table, Type>: Projection<Table, Here, Value = Type>,
+    Rhs: Operand<Type> + InScope<Src, J> { }
+
+impl<Src, Table, Type, Rhs, I, J> Condition<Src, (I, J)> for Geq<Table, Column<Table, Type>, Rhs>
+where
+    Src: Includes<Table, I>,
+    Column<Table, Type>: Projection<Table, Here, Value = Type>,
+    Rhs: Operand<Type> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Idx> Condition<Src, Idx> for IsNull<Table, Prj>
 where
-    Src: ToSql,
-    A: Condition<Src>,
-    B: Condition<Src> { }
+    Src: Includes<Table, Idx>,
+    Prj: Projection<Table, Here> { }
 
-impl<Src, A, B> Condition<Src> for Either<A, B>
+impl<Src, Table, Prj, Idx> Condition<Src, Idx> for IsNotNull<Table, Prj>
 where
-    Src: ToSql,
-    A: Condition<Src>,
-    B: Condition<Src> { }
+    Src: Includes<Table, Idx>,
+    Prj: Projection<Table, Here> { }
 
-impl<Src, A> Condition<Src> for Not<A>
+impl<Src, Table, Prj, Type, Idx> Condition<Src, Idx> for In<Table, Prj, Type>
 where
-    Src: ToSql,
-    A: Condition<Src> { }
+    Src: Includes<Table, Idx>,
+    Type: ToValue,
+    Prj: Projection<Table, Here, Value = Type> { }
 
-impl<Src, Prj, Type> Condition<Src> for Equals<Src, Prj, Type>
+impl<Src, Table, Prj, Type, Idx> Condition<Src, Idx> for NotIn<Table, Prj, Type>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> { }
+    Src: Includes<Table, Idx>,
+    Type: ToValue,
+    Prj: Projection<Table, Here, Value = Type> { }
 
-impl<Src, Prj, Type> Condition<Src> for NotEq<Src, Prj, Type>
+impl<Src, Table, Prj, Type, Idx> Condition<Src, Idx> for Between<Table, Prj, Type>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> { }
+    Src: Includes<Table, Idx>,
+    Type: ToValue,
+    Prj: Projection<Table, Here>,
+    Prj::Value: NonNull<Value = Type> { }
 
-impl<Src, Prj> Condition<Src> for IsNull<Src, Prj>
+impl<Src, Table, Prj, Type, Idx> Condition<Src, Idx> for NotBetween<Table, Prj, Type>
 where
-    Src: ToSql,
-    Prj: Projection<Src> { }
+    Src: Includes<Table, Idx>,
+    Type: ToValue,
+    Prj: Projection<Table, Here>,
+    Prj::Value: NonNull<Value = Type> { }
 
-impl<Src, Prj> Condition<Src> for IsNotNull<Src, Prj>
+impl<Src, Table, Prj, Idx> Condition<Src, Idx> for Like<Table, Prj>
 where
-    Src: ToSql,
-    Prj: Projection<Src> { }
+    Src: Includes<Table, Idx>,
+    Prj: Projection<Table, Here>,
+    Prj::Value: TextValue { }
 
-impl<Src, Prj, Type> Condition<Src> for Less<Src, Prj, Type>
+impl<Src, Table, Prj, Idx> Condition<Src, Idx> for NotLike<Table, Prj>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> { }
+    Src: Includes<Table, Idx>,
+    Prj: Projection<Table, Here>,
+    Prj::Value: TextValue { }
 
-impl<Src, Prj, Type> Condition<Src> for Greater<Src, Prj, Type>
+impl<Src, Table, Prj, Idx> Condition<Src, Idx> for ILike<Table, Prj>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> { }
+    Src: Includes<Table, Idx>,
+    Prj: Projection<Table, Here>,
+    Prj::Value: TextValue { }
+
+impl<Src, Table, Prj, Sub, Idx> Condition<Src, Idx> for InQuery<Table, Prj, Sub>
+where
+    Src: Includes<Table, Idx>,
+    Prj: Projection<Table, Here>,
+    Sub: Render { }
+
+// A subquery doesn't need anything from the source of the query it is used in.
+impl<Src, Sub: Render> Condition<Src, Here> for Exists<Sub> { }
+impl<Src, Sub: Render> Condition<Src, Here> for NotExists<Sub> { }
 
-impl<Src, Prj, Type> Condition<Src> for Leq<Src, Prj, Type>
+impl<Src, A, B, IA, IB> GroupCondition<Src, (IA, IB)> for Both<A, B>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> { }
+    A: GroupCondition<Src, IA>,
+    B: GroupCondition<Src, IB> { }
 
-impl<Src, Prj, Type> Condition<Src> for Geq<Src, Prj, Type>
+impl<Src, A, B, IA, IB> GroupCondition<Src, (IA, IB)> for Either<A, B>
 where
-    Src: ToSql,
-    Type: ToSql,
-    Prj: Projection<Src, Value = Type> { }
\ No newline at end of file
+    A: GroupCondition<Src, IA>,
+    B: GroupCondition<Src, IB> { }
+
+impl<Src, A, Idx> GroupCondition<Src, Idx> for Not<A>
+where
+    A: GroupCondition<Src, Idx> { }
+
+// Both aggregates and columns can be compared in `HAVING`. Whether the
+// columns are among those grouped by is left for the database to check.
+impl<Src, Table, Prj, Rhs, I, J> GroupCondition<Src, (I, J)> for Equals<Table, Prj, Rhs>
+where
+    Prj: Projection<Table, Here> + InScope<Src, I>,
+    Rhs: Operand<Prj::Value> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Rhs, I, J> GroupCondition<Src, (I, J)> for NotEq<Table, Prj, Rhs>
+where
+    Prj: Projection<Table, Here> + InScope<Src, I>,
+    Rhs: Operand<Prj::Value> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Rhs, I, J> GroupCondition<Src, (I, J)> for Less<Table, Prj, Rhs>
+where
+    Prj: Projection<Table, Here> + InScope<Src, I>,
+    Rhs: Operand<Prj::Value> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Rhs, I, J> GroupCondition<Src, (I, J)> for Greater<Table, Prj, Rhs>
+where
+    Prj: Projection<Table, Here> + InScope<Src, I>,
+    Rhs: Operand<Prj::Value> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Rhs, I, J> GroupCondition<Src, (I, J)> for Leq<Table, Prj, Rhs>
+where
+    Prj: Projection<Table, Here> + InScope<Src, I>,
+    Rhs: Operand<Prj::Value> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Rhs, I, J> GroupCondition<Src, (I, J)> for Geq<Table, Prj, Rhs>
+where
+    Prj: Projection<Table, Here> + InScope<Src, I>,
+    Rhs: Operand<Prj::Value> + InScope<Src, J> { }
+
+impl<Src, Table, Prj, Idx> GroupCondition<Src, Idx> for IsNull<Table, Prj> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Idx> GroupCondition<Src, Idx> for IsNotNull<Table, Prj> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Type, Idx> GroupCondition<Src, Idx> for In<Table, Prj, Type> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Type, Idx> GroupCondition<Src, Idx> for NotIn<Table, Prj, Type> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Type, Idx> GroupCondition<Src, Idx> for Between<Table, Prj, Type> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Type, Idx> GroupCondition<Src, Idx> for NotBetween<Table, Prj, Type> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Idx> GroupCondition<Src, Idx> for Like<Table, Prj> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Idx> GroupCondition<Src, Idx> for NotLike<Table, Prj> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Idx> GroupCondition<Src, Idx> for ILike<Table, Prj> where Self: Condition<Src, Idx> { }
+impl<Src, Table, Prj, Sub, Idx> GroupCondition<Src, Idx> for InQuery<Table, Prj, Sub> where Self: Condition<Src, Idx> { }
+impl<Src, Sub, Idx> GroupCondition<Src, Idx> for Exists<Sub> where Self: Condition<Src, Idx> { }
+impl<Src, Sub, Idx> GroupCondition<Src, Idx> for NotExists<Sub> where Self: Condition<Src, Idx> { }
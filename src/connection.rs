@@ -0,0 +1,106 @@
+//! Database connections.
+//!
+//! This module contains the `Connection` trait, which is how a finished `Query` gets run
+//! against an actual database. Each supported database provides its own implementation
+//! (see the `sqlite` module, which is enabled by the `sqlite` feature).
+
+use std::fmt;
+use super::{
+  builder::{Query, Statement},
+  common::{Value, Projection, FromSqlError}
+};
+
+/// A row returned by the database, with one value per projected column.
+pub type Row = Vec<Value>;
+
+/// The ways in which running a query can fail.
+#[derive(Debug)]
+pub enum Error {
+    /// The query was expected to return a row, but returned none.
+    NoRows,
+
+    /// The database returned a value that can't be represented as a `Value`.
+    UnsupportedValue(String),
+
+    /// A returned row couldn't be decoded into the query's projection.
+    Decode(FromSqlError),
+
+    /// An error reported by the database backend.
+    Backend(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NoRows => write!(f, "query returned no rows"),
+            Error::UnsupportedValue(what) => write!(f, "unsupported value: {}", what),
+            Error::Decode(e) => e.fmt(f),
+            Error::Backend(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode(e) => Some(e),
+            Error::Backend(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FromSqlError> for Error {
+    fn from(e: FromSqlError) -> Self {
+        Error::Decode(e)
+    }
+}
+
+/// A connection to a database that queries can be run on.
+///
+/// A query is rendered for one dialect when it is finished, so it should be
+/// finished in the dialect of the connection it is run on. Backends only need
+/// to provide `execute`, `fetch_rows` and `fetch_first_row`; the rest decode
+/// the returned rows into the Rust type of the query's projection.
+pub trait Connection {
+    /// Runs a statement that doesn't return rows, returning how many rows it changed.
+    fn execute<Table>(&self, statement: &Statement<Table>) -> Result<usize, Error>;
+
+    /// Runs a query, returning every row it produces as raw values.
+    fn fetch_rows<Src, Prj>(&self, query: &Query<Src, Prj>) -> Result<Vec<Row>, Error>;
+
+    /// Runs a query, returning only the first row it produces as raw values.
+    /// The rest of the rows are never read.
+    fn fetch_first_row<Src, Prj>(&self, query: &Query<Src, Prj>) -> Result<Option<Row>, Error>;
+
+    /// Runs a query, returning every row it produces.
+    fn fetch_all<Src, Prj, Idx>(&self, query: &Query<Src, Prj>) -> Result<Vec<Prj::Value>, Error>
+    where
+        Prj: Projection<Src, Idx> {
+
+        self.fetch_rows(query)?
+            .into_iter()
+            .map(|row| Ok(query.decode(row)?))
+            .collect()
+    }
+
+    /// Runs a query, returning its first row if there is one.
+    fn fetch_optional<Src, Prj, Idx>(&self, query: &Query<Src, Prj>) -> Result<Option<Prj::Value>, Error>
+    where
+        Prj: Projection<Src, Idx> {
+
+        match self.fetch_first_row(query)? {
+            Some(row) => Ok(Some(query.decode(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Runs a query, returning its first row. It is an error for the query to
+    /// return no rows.
+    fn fetch_one<Src, Prj, Idx>(&self, query: &Query<Src, Prj>) -> Result<Prj::Value, Error>
+    where
+        Prj: Projection<Src, Idx> {
+
+        self.fetch_optional(query)?.ok_or(Error::NoRows)
+    }
+}
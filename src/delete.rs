@@ -0,0 +1,164 @@
+//! SQL deletes.
+//!
+//! This module contains the builder for `DELETE` statements, whose rows are chosen with the
+//! same conditions as a query's `WHERE`. Like an update, finishing it gives a `Statement`,
+//! which is run for the number of rows it deleted rather than for rows:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "users")]
+//! struct Users {
+//!     id:         Int,
+//!     deleted_at: Nullable<DateTime>,
+//! }
+//!
+//! let statement = delete_from(USERS)
+//!     .filter(DELETED_AT.not_null())
+//!     .or_filter(ID.less_than(0))
+//!     .finish(Sqlite);
+//!
+//! assert_eq!(statement.sql, r#"DELETE FROM "users" WHERE "users"."deleted_at" IS NOT NULL OR "users"."id" < ?"#);
+//!
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE users (id INTEGER NOT NULL, deleted_at TEXT);
+//!                     INSERT INTO users VALUES (1, NULL), (2, '2019-06-01 12:30:00'), (-1, NULL);")?;
+//! assert_eq!(conn.execute(&statement)?, 2);
+//! # }
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+//!
+//! A delete has to be filtered before it can be finished, so that a table can't be emptied
+//! by forgetting to. Deleting every row takes an explicit `all_rows()`:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! # }
+//! let statement = delete_from(USERS).all_rows().finish(Postgres);
+//! assert_eq!(statement.sql, r#"DELETE FROM "users""#);
+//! ```
+//!
+//! ```compile_fail
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! # }
+//! let statement = delete_from(USERS).finish(Postgres);
+//! ```
+
+use super::{
+  builder::{Selectable, WriteStatement},
+  condition::{Condition, Predicate, Both, Either},
+  render::{Render, Renderer}
+};
+
+/// Begins a `DELETE` from the given table.
+pub fn delete_from<Table: Selectable>(table: Table) -> DeleteFrom<Table> {
+    DeleteFrom { table }
+}
+
+/// A delete that has yet to be filtered. The statement at this point
+/// is `DELETE FROM self.table`.
+pub struct DeleteFrom<Table> {
+    table: Table,
+}
+
+impl<Table> DeleteFrom<Table> {
+    /// Only deletes the rows that meet some given condition.
+    pub fn filter<Cond, Idx>(self, condition: Cond) -> FilteredDelete<Table, Cond>
+    where
+        Cond: Condition<Table, Idx> {
+
+        FilteredDelete {
+            delete: self,
+            condition,
+        }
+    }
+
+    /// Deletes every row of the table.
+    pub fn all_rows(self) -> DeleteAll<Table> {
+        DeleteAll { delete: self }
+    }
+}
+
+impl<Table: Selectable> Render for DeleteFrom<Table> {
+    fn render(&self, out: &mut Renderer) {
+        out.push("DELETE FROM ");
+        out.push_identifier(self.table.sql());
+    }
+}
+
+/// The result of filtering a delete. The statement at this point is
+/// `self.delete WHERE self.condition`.
+pub struct FilteredDelete<Table, Cond> {
+    delete:    DeleteFrom<Table>,
+    condition: Cond,
+}
+
+impl<Table, Cond> FilteredDelete<Table, Cond>
+where
+    Cond: Predicate {
+
+    /// Narrows the delete to the rows that meet both the conditions so far
+    /// and the given one.
+    pub fn filter<Other, Idx>(self, condition: Other) -> FilteredDelete<Table, Both<Cond, Other>>
+    where
+        Other: Condition<Table, Idx> {
+
+        FilteredDelete {
+            delete:    self.delete,
+            condition: self.condition.and(condition),
+        }
+    }
+
+    /// Widens the delete to the rows that meet either the conditions so far
+    /// or the given one.
+    pub fn or_filter<Other, Idx>(self, condition: Other) -> FilteredDelete<Table, Either<Cond, Other>>
+    where
+        Other: Condition<Table, Idx> {
+
+        FilteredDelete {
+            delete:    self.delete,
+            condition: self.condition.or(condition),
+        }
+    }
+}
+
+impl<Table, Cond> Render for FilteredDelete<Table, Cond>
+where
+    Table: Selectable,
+    Cond: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.delete);
+        out.push(" WHERE ");
+        out.render(&self.condition);
+    }
+}
+
+impl<Table: Selectable, Cond: Render> WriteStatement for FilteredDelete<Table, Cond> {
+    type Table = Table;
+}
+
+/// A delete of every row of a table. The statement at this point
+/// is the same as `self.delete`.
+pub struct DeleteAll<Table> {
+    delete: DeleteFrom<Table>,
+}
+
+impl<Table: Selectable> Render for DeleteAll<Table> {
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.delete);
+    }
+}
+
+impl<Table: Selectable> WriteStatement for DeleteAll<Table> {
+    type Table = Table;
+}
@@ -0,0 +1,261 @@
+//! SQL dialects.
+//!
+//! Databases agree on most of SQL, but not all of it. This module contains the `Dialect` trait,
+//! which captures the parts of the syntax that differ, along with implementations for the
+//! databases supported out of the box. A dialect is chosen when a query is finished.
+//!
+//! The default methods of `Dialect` follow standard SQL, so a new dialect only needs to
+//! override the places where its database deviates from it.
+
+use super::{
+  common::sstr,
+  schema::TypeKind
+};
+
+/// The parts of SQL syntax that differ from one database to another.
+///
+/// ```
+/// # use tuna::builder::*;
+/// let mut postgres = Renderer::new(&Postgres);
+/// let mut mysql = Renderer::new(&MySql);
+///
+/// for out in vec![&mut postgres, &mut mysql] {
+///     out.push_identifier("order");
+///     out.push(" = ");
+///     out.push_param(Value::Int(1));
+/// }
+///
+/// assert_eq!(postgres.into_parts().0, r#""order" = $1"#);
+/// assert_eq!(mysql.into_parts().0, "`order` = ?");
+/// ```
+pub trait Dialect {
+    /// Writes the placeholder for a bound parameter. `index` is the one based
+    /// position of the parameter in the query.
+    fn placeholder(&self, out: &mut String, index: usize) {
+        let _ = index;
+        out.push('?');
+    }
+
+    /// Writes an identifier (the name of a table or column), quoted so that it
+    /// cannot be mistaken for a keyword.
+    fn quote_identifier(&self, out: &mut String, ident: &str) {
+        quote_with(out, ident, '"');
+    }
+
+    /// Returns the literal for a boolean value.
+    fn boolean(&self, value: bool) -> sstr {
+        if value { "TRUE" } else { "FALSE" }
+    }
+
+    /// Whether `NULLS FIRST` and `NULLS LAST` can follow a sort direction.
+    /// Dialects without them have the ordering emulated.
+    fn supports_nulls_order(&self) -> bool {
+        true
+    }
+
+    /// Whether there is an `ILIKE` operator for case insensitive pattern matching.
+    /// Dialects without it have it emulated by comparing in lowercase.
+    fn supports_ilike(&self) -> bool {
+        false
+    }
+
+    /// Whether tables can be joined with `FULL JOIN`. Queries with one can
+    /// only be finished for dialects that implement `SupportsFullJoin`.
+    fn supports_full_join(&self) -> bool {
+        true
+    }
+
+    /// Whether `SELECT DISTINCT ON` is supported. Queries with it can only be
+    /// finished for dialects that implement `SupportsDistinctOn`.
+    fn supports_distinct_on(&self) -> bool {
+        false
+    }
+
+    /// Whether `INSERT`, `UPDATE` and `DELETE` statements can return rows
+    /// with `RETURNING`. Such statements can only be finished for dialects
+    /// that implement `SupportsReturning`.
+    fn supports_returning(&self) -> bool {
+        false
+    }
+
+    /// How an insert updates the rows it conflicts with.
+    fn upsert_syntax(&self) -> UpsertSyntax {
+        UpsertSyntax::OnConflict
+    }
+
+    /// How the number of rows a query returns is limited.
+    fn limit_syntax(&self) -> LimitSyntax {
+        LimitSyntax::FetchNext
+    }
+
+    /// Returns the name a column type is declared with.
+    fn type_name(&self, kind: TypeKind) -> String {
+        match kind {
+            TypeKind::Int => "INTEGER".to_owned(),
+            TypeKind::BigInt => "BIGINT".to_owned(),
+            TypeKind::VarChar(n) => format!("VARCHAR({})", n),
+            TypeKind::Text => "TEXT".to_owned(),
+            TypeKind::Bool => "BOOLEAN".to_owned(),
+            TypeKind::Real => "REAL".to_owned(),
+            TypeKind::Double => "DOUBLE PRECISION".to_owned(),
+            TypeKind::Date => "DATE".to_owned(),
+            TypeKind::Time => "TIME".to_owned(),
+            TypeKind::DateTime => "TIMESTAMP".to_owned(),
+            TypeKind::Blob => "BLOB".to_owned(),
+        }
+    }
+}
+
+/// The ways a dialect can limit the rows a query returns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitSyntax {
+    /// Standard SQL: `OFFSET m ROWS FETCH NEXT n ROWS ONLY`.
+    FetchNext,
+
+    /// `LIMIT n OFFSET m`. Dialects that can't have an `OFFSET` without a `LIMIT`
+    /// give the limit that stands for all rows as `unbounded`.
+    LimitOffset { unbounded: Option<sstr> },
+}
+
+/// The ways a dialect can have an insert update the rows it conflicts with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpsertSyntax {
+    /// `ON CONFLICT (columns) DO UPDATE SET column = excluded.column`, or `DO NOTHING`.
+    OnConflict,
+
+    /// `ON DUPLICATE KEY UPDATE column = VALUES(column)`, which applies to every
+    /// unique key of the table rather than the given columns.
+    OnDuplicateKey,
+}
+
+/// PostgreSQL: numbered `$1` placeholders and `"double quoted"` identifiers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Postgres;
+
+/// MySQL: `?` placeholders and `` `backtick quoted` `` identifiers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MySql;
+
+/// SQLite: `?` placeholders, `"double quoted"` identifiers and booleans as `1`/`0`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sqlite;
+
+impl Dialect for Postgres {
+    fn placeholder(&self, out: &mut String, index: usize) {
+        out.push('$');
+        out.push_str(&index.to_string());
+    }
+
+    fn supports_ilike(&self) -> bool {
+        true
+    }
+
+    fn supports_distinct_on(&self) -> bool {
+        true
+    }
+
+    fn supports_returning(&self) -> bool {
+        true
+    }
+
+    fn limit_syntax(&self) -> LimitSyntax {
+        LimitSyntax::LimitOffset { unbounded: None }
+    }
+
+    fn type_name(&self, kind: TypeKind) -> String {
+        match kind {
+            TypeKind::Blob => "BYTEA".to_owned(),
+            kind => Standard.type_name(kind),
+        }
+    }
+}
+
+impl Dialect for MySql {
+    fn quote_identifier(&self, out: &mut String, ident: &str) {
+        quote_with(out, ident, '`');
+    }
+
+    fn supports_nulls_order(&self) -> bool {
+        false
+    }
+
+    fn supports_full_join(&self) -> bool {
+        false
+    }
+
+    fn upsert_syntax(&self) -> UpsertSyntax {
+        UpsertSyntax::OnDuplicateKey
+    }
+
+    fn limit_syntax(&self) -> LimitSyntax {
+        // The largest unsigned 64 bit integer, as the MySQL manual suggests.
+        LimitSyntax::LimitOffset { unbounded: Some("18446744073709551615") }
+    }
+
+    fn type_name(&self, kind: TypeKind) -> String {
+        match kind {
+            TypeKind::Real => "FLOAT".to_owned(),
+            TypeKind::Double => "DOUBLE".to_owned(),
+            TypeKind::DateTime => "DATETIME".to_owned(),
+            kind => Standard.type_name(kind),
+        }
+    }
+}
+
+impl Dialect for Sqlite {
+    fn boolean(&self, value: bool) -> sstr {
+        if value { "1" } else { "0" }
+    }
+
+    fn supports_returning(&self) -> bool {
+        true
+    }
+
+    fn limit_syntax(&self) -> LimitSyntax {
+        LimitSyntax::LimitOffset { unbounded: Some("-1") }
+    }
+}
+
+/// The dialects whose `supports_full_join` is true, which are the only ones
+/// that a query with a `FULL JOIN` can be finished for.
+pub trait SupportsFullJoin: Dialect { }
+
+impl SupportsFullJoin for Postgres { }
+impl SupportsFullJoin for Sqlite { }
+
+/// The dialects whose `supports_distinct_on` is true, which are the only
+/// ones that a query with `DISTINCT ON` can be finished for.
+pub trait SupportsDistinctOn: Dialect { }
+
+impl SupportsDistinctOn for Postgres { }
+
+/// The dialects whose `supports_returning` is true, which are the only ones
+/// that a write statement with `RETURNING` can be finished for.
+pub trait SupportsReturning: Dialect { }
+
+impl SupportsReturning for Postgres { }
+impl SupportsReturning for Sqlite { }
+
+/// Stands for whichever dialect a query ends up being finished for, where
+/// that isn't known yet, as for a subquery. It implements none of the
+/// `Supports` traits, since not every dialect does.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AnyDialect;
+
+/// Standard SQL, used to fall back on the default methods from
+/// dialects that override them.
+struct Standard;
+
+impl Dialect for Standard { }
+
+/// Wraps `ident` in `quote`, doubling any occurrences of `quote` inside it.
+fn quote_with(out: &mut String, ident: &str, quote: char) {
+    out.push(quote);
+    for c in ident.chars() {
+        if c == quote {
+            out.push(quote);
+        }
+        out.push(c);
+    }
+    out.push(quote);
+}
@@ -0,0 +1,125 @@
+//! SQL expressions.
+//!
+//! This module contains arithmetic on numeric columns, written with Rust's own operators. An
+//! expression can be used anywhere a value of its type can, like an `UPDATE`'s `SET` or the
+//! right hand side of a comparison:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "items")]
+//! # struct Items {
+//! #     price:    Double,
+//! #     discount: Double,
+//! #     budget:   Double,
+//! # }
+//! let mut out = Renderer::new(&Sqlite);
+//! out.render(&BUDGET.geq((PRICE - DISCOUNT) * 2.0));
+//!
+//! assert_eq!(out.into_parts().0, r#""items"."budget" >= ("items"."price" - "items"."discount") * ?"#);
+//! ```
+//!
+//! Both sides have to be of the same type, so an `Int` column can't be added to a `Double`
+//! one, and a `Nullable` column is only combined with `Option`s.
+
+use std::marker::PhantomData;
+use std::ops::{Add, Sub, Mul, Div};
+use super::{
+  aggregate::Numeric,
+  column::Column,
+  common::sstr,
+  condition::{Operand, InScope},
+  render::{Renderer, Precedence}
+};
+
+/// Two operands combined by an arithmetic operator, whose result is of type `Type`.
+pub struct Arithmetic<Lhs, Rhs, Type> {
+    pub operator:   sstr,
+    pub precedence: Precedence,
+    pub left:       Lhs,
+    pub right:      Rhs,
+    _type:          PhantomData<fn() -> Type>,
+}
+
+impl<Lhs, Rhs, Type> Operand<Type> for Arithmetic<Lhs, Rhs, Type>
+where
+    Lhs: Operand<Type>,
+    Rhs: Operand<Type> {
+
+    fn render_operand(&self, out: &mut Renderer) {
+        // Operators of the same precedence group to the left, so only
+        // the right hand side needs parentheses to group the other way.
+        render_side(out, &self.left, self.left.precedence() < self.precedence);
+        out.push(self.operator);
+        render_side(out, &self.right, self.right.precedence() <= self.precedence);
+    }
+
+    fn precedence(&self) -> Precedence {
+        self.precedence
+    }
+}
+
+fn render_side<Type, O: Operand<Type>>(out: &mut Renderer, operand: &O, parenthesize: bool) {
+    if parenthesize {
+        out.push("(");
+        operand.render_operand(out);
+        out.push(")");
+    } else {
+        operand.render_operand(out);
+    }
+}
+
+impl<Src, Lhs, Rhs, Type, IL, IR> InScope<Src, (IL, IR)> for Arithmetic<Lhs, Rhs, Type>
+where
+    Lhs: InScope<Src, IL>,
+    Rhs: InScope<Src, IR> { }
+
+/// Implements an arithmetic operator for columns and the expressions built from them.
+macro_rules! impl_arithmetic {
+    ($($op:ident $method:ident $sql:expr => $precedence:ident,)*) => {
+        $(
+            impl<Table, Type, Rhs> $op<Rhs> for Column<Table, Type>
+            where
+                Type: Numeric,
+                Rhs: Operand<Type> {
+
+                type Output = Arithmetic<Self, Rhs, Type>;
+
+                fn $method(self, right: Rhs) -> Self::Output {
+                    Arithmetic {
+                        operator:   $sql,
+                        precedence: Precedence::$precedence,
+                        left:       self,
+                        right,
+                        _type:      PhantomData,
+                    }
+                }
+            }
+
+            impl<Lhs, Rhs, Type, Other> $op<Other> for Arithmetic<Lhs, Rhs, Type>
+            where
+                Type: Numeric,
+                Other: Operand<Type> {
+
+                type Output = Arithmetic<Self, Other, Type>;
+
+                fn $method(self, right: Other) -> Self::Output {
+                    Arithmetic {
+                        operator:   $sql,
+                        precedence: Precedence::$precedence,
+                        left:       self,
+                        right,
+                        _type:      PhantomData,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_arithmetic! {
+    Add add " + " => Sum,
+    Sub sub " - " => Sum,
+    Mul mul " * " => Product,
+    Div div " / " => Product,
+}
@@ -0,0 +1,530 @@
+//! SQL inserts.
+//!
+//! This module contains the builder for `INSERT` statements. A row to insert is a tuple of
+//! `(Column, value)` pairs, or a struct deriving `Insertable`, and each value has to be of the
+//! Rust type of its column:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "users")]
+//! struct Users {
+//!     id:          Int,
+//!     name:        Text,
+//!     login_count: Nullable<Int>,
+//! }
+//!
+//! let insert = insert_into(USERS)
+//!     .values(((ID, 1), (NAME, "tuna".to_owned()), (LOGIN_COUNT, None)))
+//!     .values(((ID, 2), (NAME, "trout".to_owned()), (LOGIN_COUNT, Some(3))))?
+//!     .finish(Sqlite);
+//!
+//! assert_eq!(insert.sql, r#"INSERT INTO "users" ("id", "name", "login_count") VALUES (?, ?, ?), (?, ?, ?)"#);
+//!
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL, login_count INTEGER);")?;
+//! assert_eq!(conn.execute(&insert)?, 2);
+//!
+//! let query = USERS.select((NAME, LOGIN_COUNT)).order_by(ID).finish(Sqlite);
+//! assert_eq!(conn.fetch_all(&query)?, vec![("tuna".to_owned(), None), ("trout".to_owned(), Some(3))]);
+//! # }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Every row is inserted into the columns of the first one, so adding a row that sets other
+//! columns is an error, as is inserting no rows at all:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id:   Int,
+//! #     name: Text,
+//! #     nick: Text,
+//! # }
+//! let insert = insert_into(USERS).values(((ID, 1), (NAME, "tuna".to_owned())));
+//! assert_eq!(insert.values(((ID, 2), (NICK, "trout".to_owned()))).err(),
+//!            Some(InsertError::ColumnMismatch { expected: vec!["id", "name"], found: vec!["id", "nick"] }));
+//!
+//! let rows: Vec<(Column<Users, i32>, i32)> = Vec::new();
+//! assert_eq!(insert_into(USERS).values_from(rows).err(), Some(InsertError::NoRows));
+//! ```
+//!
+//! A value of the wrong type, or a column of some other table, doesn't compile:
+//!
+//! ```compile_fail
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id:   Int,
+//! #     name: Text,
+//! # }
+//! let insert = insert_into(USERS).values(((ID, "1".to_owned()), (NAME, "tuna".to_owned())));
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+use super::{
+  aggregate::GroupBy,
+  builder::{Selectable, WriteStatement},
+  column::Column,
+  common::{ToValue, Value, sstr},
+  condition::{Operand, InScope},
+  dialect::UpsertSyntax,
+  render::{Render, Renderer},
+  source::Here,
+  update::Assignments
+};
+
+/// A row that can be inserted into the table `Table`.
+///
+/// This is implemented for a `(Column, value)` pair, for tuples of those,
+/// and by `#[derive(Insertable)]`, which sets the columns named after the
+/// fields of a struct. A row always sets at least one column:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "users")]
+/// # struct Users {
+/// #     id:   Int,
+/// #     name: Text,
+/// # }
+/// #[derive(Insertable)]
+/// #[tuna(insert_into = Users)]
+/// struct NewUser {
+///     id:   i32,
+///     name: String,
+/// }
+///
+/// let rows = vec![NewUser { id: 1, name: "tuna".to_owned() }, NewUser { id: 2, name: "trout".to_owned() }];
+/// let insert = insert_into(USERS).values_from(rows)?.finish(Postgres);
+///
+/// assert_eq!(insert.sql, r#"INSERT INTO "users" ("id", "name") VALUES ($1, $2), ($3, $4)"#);
+/// assert_eq!(insert.params[3], Value::Text("trout".to_owned()));
+/// # Ok::<(), InsertError>(())
+/// ```
+///
+/// A field named after a Rust keyword is written as a raw identifier, and
+/// sets the column of the same name without the `r#`:
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "items")]
+/// # struct Items {
+/// #     id:     Int,
+/// #     r#type: Text,
+/// # }
+/// #[derive(Insertable)]
+/// #[tuna(insert_into = Items)]
+/// struct NewItem {
+///     id:     i32,
+///     r#type: String,
+/// }
+///
+/// let insert = insert_into(ITEMS).values(NewItem { id: 1, r#type: "rod".to_owned() }).finish(Sqlite);
+/// assert_eq!(insert.sql, r#"INSERT INTO "items" ("id", "type") VALUES (?, ?)"#);
+/// ```
+pub trait Insertable<Table> {
+    /// The names of the columns that the row sets, in order.
+    fn columns(&self) -> Vec<sstr>;
+
+    /// The values that the row sets its columns to, in the same order.
+    fn values(&self) -> Vec<Value>;
+}
+
+impl<Table, Type> Insertable<Table> for (Column<Table, Type>, Type)
+where
+    Type: ToValue {
+
+    fn columns(&self) -> Vec<sstr> {
+        vec![self.0.name]
+    }
+
+    fn values(&self) -> Vec<Value> {
+        vec![self.1.to_value()]
+    }
+}
+
+/// Allows inserting tuples of pairs like ((A, a), (B, b)), ((A, a), (B, b), (C, c)) ...
+macro_rules! impl_insertable_for_tuple {
+    ($($tv:ident),* $(,)?) => {
+        impl<Table, $($tv),*> Insertable<Table> for ($((Column<Table, $tv>, $tv),)*)
+        where
+            $( $tv: ToValue, )* {
+
+            #[allow(non_snake_case)]
+            fn columns(&self) -> Vec<sstr> {
+                let ($($tv,)*) = self;
+                vec![$($tv.0.name),*]
+            }
+
+            #[allow(non_snake_case)]
+            fn values(&self) -> Vec<Value> {
+                let ($($tv,)*) = self;
+                vec![$($tv.1.to_value()),*]
+            }
+        }
+    };
+}
+
+apply_macro_for_tuples! {
+    impl_insertable_for_tuple! {
+        [A B]
+        C D E F G H I J K L M N O P Q R S T U V W X Y Z
+    }
+}
+
+/// The ways in which the rows given to an insert can be wrong.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InsertError {
+    /// There were no rows to insert.
+    NoRows,
+
+    /// A row set other columns than the first row of the insert did.
+    ColumnMismatch {
+        expected: Vec<sstr>,
+        found:    Vec<sstr>,
+    },
+}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InsertError::NoRows => write!(f, "an insert needs at least one row"),
+            InsertError::ColumnMismatch { expected, found } =>
+                write!(f, "expected a row setting {:?}, found one setting {:?}", expected, found),
+        }
+    }
+}
+
+impl std::error::Error for InsertError { }
+
+/// Binds the value to be inserted into a column, which has to be of the
+/// column's type. This is what `#[derive(Insertable)]` checks fields with.
+pub fn insert_value<Table, Type: ToValue>(_column: Column<Table, Type>, value: &Type) -> Value {
+    value.to_value()
+}
+
+/// Begins an `INSERT` into the given table.
+pub fn insert_into<Table: Selectable>(table: Table) -> InsertInto<Table> {
+    InsertInto { table }
+}
+
+/// An insert that is waiting for its rows. The statement at this point
+/// is `INSERT INTO self.table`.
+pub struct InsertInto<Table> {
+    table: Table,
+}
+
+impl<Table> InsertInto<Table> {
+    /// Gives the first row to insert, whose columns every other row has to set.
+    pub fn values<Row>(self, row: Row) -> Insert<Table, Row>
+    where
+        Row: Insertable<Table> {
+
+        Insert {
+            table:   self.table,
+            columns: row.columns(),
+            rows:    vec![row],
+        }
+    }
+
+    /// Gives the rows to insert, which fails if there are none or if they
+    /// don't all set the same columns.
+    pub fn values_from<Row, I>(self, rows: I) -> Result<Insert<Table, Row>, InsertError>
+    where
+        Table: Selectable,
+        Row: Insertable<Table>,
+        I: IntoIterator<Item = Row> {
+
+        let mut rows = rows.into_iter();
+        match rows.next() {
+            Some(first) => self.values(first).values_from(rows),
+            None => Err(InsertError::NoRows),
+        }
+    }
+}
+
+/// An insert of one or more rows. The statement at this point is
+/// `INSERT INTO self.table (self.columns) VALUES self.rows`.
+pub struct Insert<Table, Row> {
+    table:   Table,
+    columns: Vec<sstr>,
+    rows:    Vec<Row>,
+}
+
+impl<Table, Row> Insert<Table, Row>
+where
+    Table: Selectable,
+    Row: Insertable<Table> {
+
+    /// Inserts another row, which fails if it doesn't set the same columns
+    /// as the first one.
+    pub fn values(mut self, row: Row) -> Result<Self, InsertError> {
+        let found = row.columns();
+        if found != self.columns {
+            return Err(InsertError::ColumnMismatch { expected: self.columns, found });
+        }
+
+        self.rows.push(row);
+        Ok(self)
+    }
+
+    /// Inserts each of the given rows, which fails if any of them doesn't
+    /// set the same columns as the first one.
+    pub fn values_from<I>(self, rows: I) -> Result<Self, InsertError>
+    where
+        I: IntoIterator<Item = Row> {
+
+        rows.into_iter().try_fold(self, Insert::values)
+    }
+
+    /// Decides what happens to the rows that conflict with an existing row
+    /// on the given columns, which have to be a unique key of the table.
+    pub fn on_conflict<Cols, Idx>(self, columns: Cols) -> OnConflict<Table, Row, Cols>
+    where
+        Cols: GroupBy<Table, Idx> {
+
+        OnConflict {
+            insert: self,
+            target: columns,
+        }
+    }
+}
+
+impl<Table, Row> WriteStatement for Insert<Table, Row>
+where
+    Table: Selectable,
+    Row: Insertable<Table> {
+
+    type Table = Table;
+}
+
+impl<Table, Row> Render for Insert<Table, Row>
+where
+    Table: Selectable,
+    Row: Insertable<Table> {
+
+    fn render(&self, out: &mut Renderer) {
+        out.push("INSERT INTO ");
+        out.push_identifier(self.table.sql());
+        out.push(" (");
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                out.push(", ");
+            }
+            out.push_identifier(column);
+        }
+        out.push(") VALUES ");
+
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                out.push(", ");
+            }
+            out.push("(");
+            for (j, value) in row.values().into_iter().enumerate() {
+                if j > 0 {
+                    out.push(", ");
+                }
+                out.push_param(value);
+            }
+            out.push(")");
+        }
+    }
+}
+
+/// An insert that is waiting for what to do about conflicting rows.
+pub struct OnConflict<Table, Row, Cols> {
+    insert: Insert<Table, Row>,
+    target: Cols,
+}
+
+impl<Table, Row, Cols> OnConflict<Table, Row, Cols> {
+    /// Skips the rows that conflict, leaving the existing ones as they are.
+    pub fn do_nothing(self) -> Upsert<Table, Row, Cols, DoNothing> {
+        Upsert {
+            insert: self.insert,
+            target: self.target,
+            action: DoNothing,
+        }
+    }
+
+    /// Updates the existing rows that conflict instead. The assignments are
+    /// given by a function of the row that was to be inserted, whose values
+    /// can be used alongside the existing row's columns.
+    pub fn do_update<F, A, Idx>(self, assignments: F) -> Upsert<Table, Row, Cols, DoUpdate<A::Set>>
+    where
+        F: FnOnce(Excluded<Table>) -> A,
+        A: Assignments<Table, Idx> {
+
+        Upsert {
+            insert: self.insert,
+            target: self.target,
+            action: DoUpdate {
+                assignments: assignments(Excluded { _table: PhantomData }).into_assignments(),
+            },
+        }
+    }
+}
+
+/// What an upsert does to the existing rows that it conflicts with.
+pub trait ConflictAction {
+    /// The assignments that the rows are updated with, or `None` if they are
+    /// left as they are.
+    fn assignments(&self) -> Option<&dyn Render>;
+}
+
+/// Leaves the conflicting rows as they are.
+pub struct DoNothing;
+
+/// Updates the conflicting rows with the given assignments.
+pub struct DoUpdate<Set> {
+    pub assignments: Set,
+}
+
+impl ConflictAction for DoNothing {
+    fn assignments(&self) -> Option<&dyn Render> {
+        None
+    }
+}
+
+impl<Set: Render> ConflictAction for DoUpdate<Set> {
+    fn assignments(&self) -> Option<&dyn Render> {
+        Some(&self.assignments)
+    }
+}
+
+/// The row that an upsert was to insert, before it conflicted with an existing one.
+pub struct Excluded<Table> {
+    _table: PhantomData<fn(&Table)>,
+}
+
+impl<Table> Excluded<Table> {
+    /// The value that was to be inserted into a column.
+    pub fn value<Type>(&self, column: Column<Table, Type>) -> ExcludedValue<Table, Type> {
+        ExcludedValue { column }
+    }
+}
+
+/// The value that an upsert was to insert into a column, which a conflicting
+/// row's columns can be set to.
+pub struct ExcludedValue<Table, Type> {
+    column: Column<Table, Type>,
+}
+
+impl<Table, Type> Operand<Type> for ExcludedValue<Table, Type> {
+    fn render_operand(&self, out: &mut Renderer) {
+        match out.dialect().upsert_syntax() {
+            UpsertSyntax::OnConflict => {
+                out.push("excluded.");
+                out.push_identifier(self.column.name);
+            }
+            UpsertSyntax::OnDuplicateKey => {
+                out.push("VALUES(");
+                out.push_identifier(self.column.name);
+                out.push(")");
+            }
+        }
+    }
+}
+
+impl<Table, Type> InScope<Table, Here> for ExcludedValue<Table, Type> { }
+
+/// An insert that updates or skips the rows it conflicts with. The statement at
+/// this point is `self.insert ON CONFLICT (self.target) DO UPDATE SET self.action`,
+/// or `DO NOTHING` if the action is `DoNothing`, in whatever form the dialect has.
+///
+/// ```
+/// # use tuna::builder::*;
+/// # #[derive(Tuna)]
+/// # #[tuna(table = "visits")]
+/// # struct Visits {
+/// #     page:  Text,
+/// #     count: Int,
+/// # }
+/// let upsert = || insert_into(VISITS)
+///     .values(((PAGE, "/".to_owned()), (COUNT, 1)))
+///     .on_conflict(PAGE)
+///     .do_update(|excluded| (COUNT, COUNT + excluded.value(COUNT)));
+///
+/// let statement = upsert().finish(Sqlite);
+/// assert_eq!(statement.sql, r#"INSERT INTO "visits" ("page", "count") VALUES (?, ?) ON CONFLICT ("page") DO UPDATE SET "count" = "visits"."count" + excluded."count""#);
+///
+/// # #[cfg(feature = "sqlite")] {
+/// let conn = SqliteConnection::open_in_memory()?;
+/// conn.execute_batch("CREATE TABLE visits (page TEXT PRIMARY KEY, count INTEGER NOT NULL);")?;
+/// for _ in 0..3 {
+///     conn.execute(&statement)?;
+/// }
+/// assert_eq!(conn.fetch_one(&VISITS.select(COUNT).finish(Sqlite))?, 3);
+///
+/// // MySQL updates on a conflict with any unique key.
+/// let statement = upsert().finish(MySql);
+/// assert_eq!(statement.sql, "INSERT INTO `visits` (`page`, `count`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `count` = `visits`.`count` + VALUES(`count`)");
+///
+/// let skip = || insert_into(VISITS).values(((PAGE, "/".to_owned()), (COUNT, 1))).on_conflict(PAGE).do_nothing();
+/// assert_eq!(skip().finish(Postgres).sql, r#"INSERT INTO "visits" ("page", "count") VALUES ($1, $2) ON CONFLICT ("page") DO NOTHING"#);
+/// assert_eq!(skip().finish(MySql).sql, "INSERT INTO `visits` (`page`, `count`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `page` = `page`");
+/// # }
+/// # Ok::<(), tuna::connection::Error>(())
+/// ```
+pub struct Upsert<Table, Row, Cols, Action> {
+    insert: Insert<Table, Row>,
+    target: Cols,
+    action: Action,
+}
+
+impl<Table, Row, Cols, Action> Render for Upsert<Table, Row, Cols, Action>
+where
+    Table: Selectable,
+    Row: Insertable<Table>,
+    Cols: Render,
+    Action: ConflictAction {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.insert);
+
+        match out.dialect().upsert_syntax() {
+            UpsertSyntax::OnConflict => {
+                out.push(" ON CONFLICT (");
+                out.render(&self.target);
+                match self.action.assignments() {
+                    None => out.push(") DO NOTHING"),
+                    Some(assignments) => {
+                        out.push(") DO UPDATE SET ");
+                        out.render(assignments);
+                    }
+                }
+            }
+            UpsertSyntax::OnDuplicateKey => {
+                out.push(" ON DUPLICATE KEY UPDATE ");
+                match (self.action.assignments(), self.insert.columns.first()) {
+                    (Some(assignments), _) => out.render(assignments),
+                    // Setting a column to itself changes nothing, which skips the row.
+                    (None, Some(column)) => {
+                        out.push_identifier(column);
+                        out.push(" = ");
+                        out.push_identifier(column);
+                    }
+                    // An insert always has columns.
+                    (None, None) => (),
+                }
+            }
+        }
+    }
+}
+
+impl<Table, Row, Cols, Action> WriteStatement for Upsert<Table, Row, Cols, Action>
+where
+    Table: Selectable,
+    Row: Insertable<Table>,
+    Cols: Render,
+    Action: ConflictAction {
+
+    type Table = Table;
+}
@@ -3,38 +3,60 @@
 //! This crate is a general purpose library for building "fluent" SQL queries in Rust. Importantly, the idea
 //! is to have the library prevent SQL injections and allow queried and returned data to be typed.
 //!
-//! In an ideal world, this is how the library would be used by the client.
+//! This is how the library is used by the client.
 //! ```
-//! // The client provides the DB schema using `tuna::SqlType` that map Sql types to Rust types.
-//! // Deriving Tuna generates a lot of utility code from macros, such as defining columns and
-//! // default trait implementations.
+//! use tuna::builder::*;
+//!
+//! // The client provides the DB schema using `SqlType`s (`Int`, `VarChar<N>`, `Nullable<T>`, ...)
+//! // that map Sql types to Rust types. Deriving Tuna generates a lot of utility code from macros,
+//! // such as defining columns and default trait implementations.
 //! #[derive(Tuna)]
-//! struct UserTable {
-//!     id: SqlType::Int
-//!     login_count: SqlType::Int,
-//!     name: SqlType::VarChar,
-//!     deleted_at: SqlType::Nullable(SqlType::DateTime)
+//! struct Users {
+//!     id:          Int,
+//!     login_count: Int,
+//!     name:        VarChar<255>,
+//!     deleted_at:  Nullable<DateTime>,
 //! }
 //! ```
 //!
 //! Then a query would be constructed like this:
 //! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # struct Users {
+//! #     id:          Int,
+//! #     login_count: Int,
+//! #     name:        VarChar<255>,
+//! #     deleted_at:  Nullable<DateTime>,
+//! # }
 //! let query = USERS
 //!     .select((ID, LOGIN_COUNT))
 //!     .filter(ID.geq(5)
-//!               .and(LOGIN_COUNT.not_null()))
-//!     .finish();
+//!               .and(DELETED_AT.null()))
+//!     .finish(Postgres);
 //!
-//! // `query` can now be executed with the DB credentials.
+//! // `query` can now be executed on a `Connection` to a Postgres database,
+//! // giving back rows of type `(i32, i32)`.
 //! ```
 //!
-//! Right now, the library supports the "query building" part, but automatic code generation from macros
-//! has not been fully implemented. As such, the user will have to manually define the columns. Likewise,
-//! `tuna::SqlType` is a work in a progress so a user will have to manually define the Rust types he expects
-//! to get back. See `main.rs` for an example of current functionality.
+//! See `main.rs` for an example of current functionality.
 
 pub mod builder;
 
+#[macro_use]
 pub mod common;
+pub mod aggregate;
 pub mod column;
 pub mod condition;
+pub mod connection;
+pub mod delete;
+pub mod dialect;
+pub mod expression;
+pub mod insert;
+pub mod order;
+pub mod render;
+pub mod schema;
+pub mod source;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod update;
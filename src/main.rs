@@ -1,6 +1,5 @@
 extern crate tuna;
 
-use std::marker::PhantomData;
 use tuna::builder::*;
 
 fn main() {
@@ -10,66 +9,19 @@ fn main() {
         .filter(ID.geq(1)
                   .and(LOGIN_COUNT.less_than(Some(5))
                   .and(NAME.equals("tuna".to_owned()))))
-        .finish();
+        .order_by(ID.desc())
+        .finish(Postgres);
 
     println!("{:?}", query.sql);
+    println!("{:?}", query.params);
 }
 
-// The user would only need to define something like this.
-// #[derive(Tuna)]
-// struct UserTable {
-//    id: SqlType::Int
-//    login_count: SqlType::Nullable(SqlType::Int),
-//    name: SqlType::VarChar,
-// }
-
-// Macro generated code all below here.
-#[derive(Default, Debug)]
+// Deriving `Tuna` generates the `USERTABLE` constant, a `Column` constant
+// for each field (`ID`, `LOGIN_COUNT` and `NAME`) and the trait
+// implementations needed to select from the table.
+#[derive(Tuna, Debug)]
 struct UserTable {
-    id: PhantomData<fn(&i64)>,
-    login_count: PhantomData<fn(&Option<i64>)>,
-    name: PhantomData<fn(&String)>,
-}
-
-const USERTABLE: UserTable = UserTable {
-    id: PhantomData,
-    login_count: PhantomData,
-    name: PhantomData
-};
-
-const ID: Column<UserTable, i64> = Column {
-    name:     "id",
-    position: 0,
-    parse:    |s| s.parse().unwrap(),
-    _table_marker:  PhantomData,
-};
-
-const NAME: Column<UserTable, String> = Column {
-    name:     "name",
-    position: 0,
-    parse:    |s| s.parse().unwrap(),
-    _table_marker:  PhantomData,
-};
-
-const LOGIN_COUNT: Column<UserTable, Option<i64>> = Column {
-    name:     "login_count",
-    position: 3,
-    parse:    |s| s.parse().ok(),
-    _table_marker:  PhantomData,
-};
-
-impl ToSql for UserTable {
-    type Sql = sstr;
-    fn sql(&self) -> Self::Sql {
-        "UserTable"
-    }
+    id: Int,
+    login_count: Nullable<Int>,
+    name: Text,
 }
-
-impl Selectable for UserTable {
-    fn select<P: Projection<UserTable>>(self, p: P) -> Selected<Self, P> {
-        Selected {
-            source: self,
-            projection: p,
-        }
-    }
-}
\ No newline at end of file
@@ -0,0 +1,182 @@
+//! SQL ordering.
+//!
+//! This module contains definitions and implementations related to sorting the results of a
+//! query. That is, things that go after the `ORDER BY` clause.
+//!
+//! A query can be ordered by a column of its source or an aggregate of one, a `Sort` made from
+//! either with `asc()` or `desc()`, or a tuple of these to sort by several columns in turn:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! struct Users {
+//!     id:        Int,
+//!     last_seen: Nullable<DateTime>,
+//! }
+//!
+//! let query = USERS
+//!     .select(ID)
+//!     .order_by((LAST_SEEN.desc().nulls_last(), ID.asc()))
+//!     .finish(Postgres);
+//!
+//! assert_eq!(query.sql, r#"SELECT "id" FROM "Users" ORDER BY "last_seen" DESC NULLS LAST, "id" ASC"#);
+//!
+//! // MySQL has no `NULLS LAST`, so it sorts on whether the column is null first.
+//! let query = USERS
+//!     .select(ID)
+//!     .order_by(LAST_SEEN.desc().nulls_last())
+//!     .finish(MySql);
+//!
+//! assert_eq!(query.sql, "SELECT `id` FROM `Users` ORDER BY `last_seen` IS NULL ASC, `last_seen` DESC");
+//! ```
+//!
+//! Sorting by an aggregate orders the groups of a grouped query:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "orders")]
+//! # struct Orders {
+//! #     user_id: Int,
+//! #     total:   Double,
+//! # }
+//! let query = ORDERS
+//!     .select((USER_ID, count_all()))
+//!     .group_by(USER_ID)
+//!     .order_by((count_all().desc(), max(TOTAL)))
+//!     .finish(Sqlite);
+//!
+//! assert_eq!(query.sql, r#"SELECT "user_id", COUNT(*) FROM "orders" GROUP BY "user_id" ORDER BY COUNT(*) DESC, MAX("total")"#);
+//!
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE orders (user_id INTEGER NOT NULL, total REAL NOT NULL);
+//!                     INSERT INTO orders VALUES (1, 2.0), (2, 4.0), (2, 8.0);")?;
+//! assert_eq!(conn.fetch_all(&query)?, vec![(2, 2), (1, 1)]);
+//! # }
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+
+use super::{
+  aggregate::{Aggregate, CountAll},
+  common::Projection,
+  column::Column,
+  render::{Render, Renderer}
+};
+
+/// A trait to represent what a query can be ordered by. Like `Condition`, the
+/// parameters are the source of the data and where the columns are found in
+/// it, so that only columns from the query's own source can be used.
+pub trait OrderBy<Src, Idx>: Render { }
+
+/// The direction to sort in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+/// Where to put nulls when sorting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Nulls {
+    First,
+    Last,
+}
+
+impl Direction {
+    fn sql(self) -> &'static str {
+        match self {
+            Direction::Asc => " ASC",
+            Direction::Desc => " DESC",
+        }
+    }
+}
+
+/// A column or other projection to sort by, along with how to sort it.
+pub struct Sort<Prj> {
+    pub projection: Prj,
+    pub direction:  Direction,
+    pub nulls:      Option<Nulls>,
+}
+
+impl<Prj> Sort<Prj> {
+    /// Sorts in ascending order.
+    pub fn asc(self) -> Self {
+        Sort { direction: Direction::Asc, ..self }
+    }
+
+    /// Sorts in descending order.
+    pub fn desc(self) -> Self {
+        Sort { direction: Direction::Desc, ..self }
+    }
+
+    /// Puts nulls before all other values.
+    pub fn nulls_first(self) -> Self {
+        Sort { nulls: Some(Nulls::First), ..self }
+    }
+
+    /// Puts nulls after all other values.
+    pub fn nulls_last(self) -> Self {
+        Sort { nulls: Some(Nulls::Last), ..self }
+    }
+}
+
+impl<Prj: Render> Render for Sort<Prj> {
+    fn render(&self, out: &mut Renderer) {
+        match self.nulls {
+            Some(nulls) if !out.dialect().supports_nulls_order() => {
+                // Sorting on whether the value is null first has the same effect.
+                out.render(&self.projection);
+                out.push(match nulls {
+                    Nulls::First => " IS NULL DESC, ",
+                    Nulls::Last => " IS NULL ASC, ",
+                });
+                out.render(&self.projection);
+                out.push(self.direction.sql());
+            }
+            nulls => {
+                out.render(&self.projection);
+                out.push(self.direction.sql());
+                match nulls {
+                    Some(Nulls::First) => out.push(" NULLS FIRST"),
+                    Some(Nulls::Last) => out.push(" NULLS LAST"),
+                    None => (),
+                }
+            }
+        }
+    }
+}
+
+impl<Src, Prj, Idx> OrderBy<Src, Idx> for Sort<Prj>
+where
+    Prj: Projection<Src, Idx> { }
+
+impl<Src, Table, Type, Idx> OrderBy<Src, Idx> for Column<Table, Type>
+where
+    Column<Table, Type>: Projection<Src, Idx> { }
+
+impl<Src, Arg, Value, Idx> OrderBy<Src, Idx> for Aggregate<Arg, Value>
+where
+    Aggregate<Arg, Value>: Projection<Src, Idx> { }
+
+impl<Src, Idx> OrderBy<Src, Idx> for CountAll
+where
+    CountAll: Projection<Src, Idx> { }
+
+/// Allows ordering by tuples like (A, B), (A, B, C) ...
+macro_rules! impl_order_by_for_tuple {
+    ($(($tv:ident $ti:ident)),* $(,)?) => {
+        impl<Src, $($tv, $ti),*> OrderBy<Src, ($($ti,)*)> for ($($tv,)*)
+        where
+            $( $tv: OrderBy<Src, $ti>, )* { }
+    };
+}
+
+apply_macro_for_tuples! {
+    impl_order_by_for_tuple! {
+        [(A IA) (B IB)]
+        (C IC) (D ID) (E IE) (F IF) (G IG) (H IH) (I II) (J IJ) (K IK) (L IL) (M IM)
+        (N IN) (O IO) (P IP) (Q IQ) (R IR) (S IS) (T IT) (U IU) (V IV) (W IW) (X IX)
+        (Y IY) (Z IZ)
+    }
+}
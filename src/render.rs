@@ -0,0 +1,131 @@
+//! SQL rendering.
+//!
+//! This module contains the machinery for turning a query into SQL text. Unlike `ToSql`, which
+//! only produces something displayable, rendering also collects the values a query compares
+//! against as bound parameters. Those values never become part of the SQL string itself, which
+//! is what keeps user supplied data from being interpreted as SQL.
+
+use std::fmt::{Display, Write};
+use super::{
+  common::Value,
+  dialect::Dialect
+};
+
+/// Accumulates SQL text and its bound parameters while a query is rendered
+/// for a particular SQL dialect.
+pub struct Renderer<'d> {
+    dialect: &'d dyn Dialect,
+    sql:     String,
+    params:  Vec<Value>,
+    qualify: bool,
+}
+
+impl<'d> Renderer<'d> {
+    /// Creates an empty renderer for the given dialect.
+    pub fn new(dialect: &'d dyn Dialect) -> Self {
+        Renderer {
+            dialect,
+            sql:     String::new(),
+            params:  Vec::new(),
+            qualify: false,
+        }
+    }
+
+    /// The dialect being rendered for.
+    pub fn dialect(&self) -> &'d dyn Dialect {
+        self.dialect
+    }
+
+    /// Appends some raw SQL text.
+    pub fn push<T: Display>(&mut self, sql: T) {
+        // Writing into a `String` cannot fail.
+        write!(self.sql, "{}", sql).unwrap();
+    }
+
+    /// Appends an identifier, quoted as the dialect requires.
+    pub fn push_identifier<T: Display>(&mut self, ident: T) {
+        self.dialect.quote_identifier(&mut self.sql, &ident.to_string());
+    }
+
+    /// Appends a placeholder and binds `value` to it.
+    pub fn push_param(&mut self, value: Value) {
+        self.params.push(value);
+        self.dialect.placeholder(&mut self.sql, self.params.len());
+    }
+
+    /// Appends something renderable.
+    pub fn render<R: Render + ?Sized>(&mut self, item: &R) {
+        item.render(self);
+    }
+
+    /// Appends something renderable that is an operand of an operator with the
+    /// given precedence, wrapping it in parentheses if it binds less tightly.
+    pub fn render_operand<R: Render + ?Sized>(&mut self, item: &R, parent: Precedence) {
+        if item.precedence() < parent {
+            self.push("(");
+            item.render(self);
+            self.push(")");
+        } else {
+            item.render(self);
+        }
+    }
+
+    /// Whether columns are written along with the name of their table.
+    pub fn qualifies_columns(&self) -> bool {
+        self.qualify
+    }
+
+    /// From here on, writes columns along with the name of their table, as is
+    /// needed once a query has more than one table.
+    pub fn qualify_columns(&mut self) {
+        self.qualify = true;
+    }
+
+    /// Appends something renderable, writing any columns in it along with the
+    /// name of their table.
+    pub fn render_qualified<R: Render + ?Sized>(&mut self, item: &R) {
+        let qualify = std::mem::replace(&mut self.qualify, true);
+        item.render(self);
+        self.qualify = qualify;
+    }
+
+    /// Appends a subquery in parentheses. It is rendered along with everything
+    /// else, so its parameters are numbered in order with the ones around it,
+    /// but it decides for itself whether its columns need their table.
+    pub fn render_subquery<R: Render + ?Sized>(&mut self, query: &R) {
+        let qualify = std::mem::replace(&mut self.qualify, false);
+        self.push("(");
+        query.render(self);
+        self.push(")");
+        self.qualify = qualify;
+    }
+
+    /// Consumes the renderer, giving back the SQL and the parameters in the order
+    /// their placeholders appear.
+    pub fn into_parts(self) -> (String, Vec<Value>) {
+        (self.sql, self.params)
+    }
+}
+
+/// Things that can be rendered into SQL, possibly binding parameters along the way.
+pub trait Render {
+    /// Writes the SQL for this thing into `out`.
+    fn render(&self, out: &mut Renderer);
+
+    /// How tightly the rendered SQL binds when it is used as an operand. Anything
+    /// that isn't built from an operator can't be split apart, so binds tightest.
+    fn precedence(&self) -> Precedence {
+        Precedence::Atom
+    }
+}
+
+/// The precedence levels of SQL operators, from loosest to tightest binding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precedence {
+    Or,
+    And,
+    Not,
+    Sum,
+    Product,
+    Atom,
+}
@@ -0,0 +1,302 @@
+//! SQL schema types.
+//!
+//! This module contains the vocabulary used to describe a table to `#[derive(Tuna)]`. Each field
+//! of a table definition is one of the SQL types below, which are only markers: they hold no data,
+//! but determine the Rust type of the column's values and how the column is declared in DDL.
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "users")]
+//! struct UserTable {
+//!     id:         Int,
+//!     name:       VarChar<64>,
+//!     deleted_at: Nullable<DateTime>,
+//! }
+//!
+//! // `ID` is a `Column<UserTable, i32>` and `DELETED_AT` a `Column<UserTable, Option<DateTimeValue>>`.
+//! let query = USERTABLE.select((ID, DELETED_AT)).filter(NAME.equals("tuna".to_owned())).finish(Sqlite);
+//!
+//! assert_eq!(UserTable::create_table(Postgres),
+//!            r#"CREATE TABLE "users" ("id" INTEGER NOT NULL, "name" VARCHAR(64) NOT NULL, "deleted_at" TIMESTAMP)"#);
+//! ```
+//!
+//! Dates and times are kept as strings in ISO 8601 format (e.g. `2019-06-01 12:30:00`), which
+//! compare correctly as text and are understood by all supported databases. Their values have
+//! types of their own (`DateValue`, `TimeValue` and `DateTimeValue`) that can only be parsed
+//! from text in that format, so that they aren't mixed up with other strings:
+//!
+//! ```compile_fail
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     nick:       Nullable<Text>,
+//! #     deleted_at: Nullable<DateTime>,
+//! # }
+//! let condition = DELETED_AT.equals(NICK);
+//! ```
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     deleted_at: Nullable<DateTime>,
+//! # }
+//! let deleted_at: DateTimeValue = "2019-06-01 12:30:00".parse().unwrap();
+//! let query = USERS.select(DELETED_AT).filter(DELETED_AT.less_than(Some(deleted_at))).finish(Sqlite);
+//! ```
+
+use std::ops::Range;
+use super::{
+  builder::Selectable,
+  common::{ToValue, NonNull, FromSql, FromSqlError, Value, invalid, sstr},
+  dialect::Dialect,
+  render::Renderer
+};
+
+/// A SQL type that a column can be declared as.
+pub trait SqlType {
+    /// The Rust type of the values in a column of this type.
+    type Value;
+
+    /// How the type is named in DDL.
+    const KIND: TypeKind;
+
+    /// Whether a column of this type can hold nulls.
+    const NULLABLE: bool = false;
+
+    /// A value of the marker type, used to build a table's constant.
+    const MARKER: Self;
+}
+
+/// The SQL types that aren't nullable. Only these can be made `Nullable`.
+pub trait NotNull: SqlType { }
+
+/// The name of a SQL type, independent of nullability. Each dialect decides
+/// how it is spelled (see `Dialect::type_name`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeKind {
+    Int,
+    BigInt,
+    VarChar(usize),
+    Text,
+    Bool,
+    Real,
+    Double,
+    Date,
+    Time,
+    DateTime,
+    Blob,
+}
+
+/// A column that can hold null in addition to the values of `T`. Its values
+/// are `Option`s of `T`'s values.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Nullable<T>(std::marker::PhantomData<T>);
+
+impl<T: NotNull> SqlType for Nullable<T> {
+    type Value = Option<T::Value>;
+    const KIND: TypeKind = T::KIND;
+    const NULLABLE: bool = true;
+    const MARKER: Self = Nullable(std::marker::PhantomData);
+}
+
+/// A variable length string of at most `N` characters.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VarChar<const N: usize>;
+
+impl<const N: usize> SqlType for VarChar<N> {
+    type Value = String;
+    const KIND: TypeKind = TypeKind::VarChar(N);
+    const MARKER: Self = VarChar;
+}
+
+impl<const N: usize> NotNull for VarChar<N> { }
+
+/// Declares unit marker types for SQL types, along with their Rust types.
+macro_rules! sql_types {
+    ($($(#[$doc:meta])* $name:ident => $value:ty,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Copy, Debug, Default)]
+            pub struct $name;
+
+            impl SqlType for $name {
+                type Value = $value;
+                const KIND: TypeKind = TypeKind::$name;
+                const MARKER: Self = $name;
+            }
+
+            impl NotNull for $name { }
+        )*
+    };
+}
+
+sql_types! {
+    /// A 32 bit integer.
+    Int => i32,
+    /// A 64 bit integer.
+    BigInt => i64,
+    /// A string of any length.
+    Text => String,
+    /// A boolean.
+    Bool => bool,
+    /// A single precision floating point number.
+    Real => f32,
+    /// A double precision floating point number.
+    Double => f64,
+    /// A date, as `YYYY-MM-DD`.
+    Date => DateValue,
+    /// A time of day, as `HH:MM:SS`.
+    Time => TimeValue,
+    /// A date and time, as `YYYY-MM-DD HH:MM:SS`.
+    DateTime => DateTimeValue,
+    /// Binary data.
+    Blob => Vec<u8>,
+}
+
+/// Declares the value types of dates and times, which hold text that `$valid`
+/// has checked to be in the right format.
+macro_rules! temporal_values {
+    ($($(#[$doc:meta])* $name:ident, $expected:expr => $valid:ident,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(String);
+
+            impl $name {
+                /// The value as text.
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl std::str::FromStr for $name {
+                type Err = FromSqlError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    $name::from_sql(Value::Text(s.to_owned()))
+                }
+            }
+
+            impl std::fmt::Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    f.write_str(&self.0)
+                }
+            }
+
+            impl ToValue for $name {
+                fn to_value(&self) -> Value {
+                    Value::Text(self.0.clone())
+                }
+            }
+
+            impl NonNull for $name {
+                type Value = $name;
+            }
+
+            impl FromSql for $name {
+                fn from_sql(value: Value) -> Result<Self, FromSqlError> {
+                    match value {
+                        Value::Text(a) if $valid(&a) => Ok($name(a)),
+                        found => invalid($expected, found),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+temporal_values! {
+    /// The value of a `Date` column. It is parsed from `YYYY-MM-DD` text:
+    ///
+    /// ```
+    /// # use tuna::builder::*;
+    /// let date: DateValue = "2019-06-01".parse().unwrap();
+    /// assert_eq!(date.as_str(), "2019-06-01");
+    /// assert!("2019-13-01".parse::<DateValue>().is_err());
+    /// assert!("not a date".parse::<DateValue>().is_err());
+    /// ```
+    DateValue, "a date (YYYY-MM-DD)" => is_date,
+    /// The value of a `Time` column, parsed from `HH:MM:SS` text, which may
+    /// be followed by fractions of a second.
+    TimeValue, "a time (HH:MM:SS)" => is_time,
+    /// The value of a `DateTime` column, parsed from `YYYY-MM-DD HH:MM:SS`
+    /// text, which may be followed by fractions of a second.
+    DateTimeValue, "a date and time (YYYY-MM-DD HH:MM:SS)" => is_date_time,
+}
+
+/// Whether `s` is laid out like `pattern`, in which each `9` stands for any digit.
+fn has_layout(s: &str, pattern: &str) -> bool {
+    s.len() == pattern.len() && s.bytes().zip(pattern.bytes()).all(|(c, p)| match p {
+        b'9' => c.is_ascii_digit(),
+        p => c == p,
+    })
+}
+
+/// The number written by the digits at `range` of `s`, which `has_layout` has checked.
+fn number(s: &str, range: Range<usize>) -> u32 {
+    s[range].parse().unwrap()
+}
+
+fn is_date(s: &str) -> bool {
+    has_layout(s, "9999-99-99")
+        && (1..=12).contains(&number(s, 5..7))
+        && (1..=31).contains(&number(s, 8..10))
+}
+
+fn is_time(s: &str) -> bool {
+    let (time, fraction) = match s.split_once('.') {
+        Some((time, fraction)) => (time, Some(fraction)),
+        None => (s, None),
+    };
+    has_layout(time, "99:99:99")
+        && number(time, 0..2) < 24
+        && number(time, 3..5) < 60
+        && number(time, 6..8) < 60
+        && fraction.is_none_or(|f| !f.is_empty() && f.bytes().all(|c| c.is_ascii_digit()))
+}
+
+fn is_date_time(s: &str) -> bool {
+    match s.split_once(' ') {
+        Some((date, time)) => is_date(date) && is_time(time),
+        None => false,
+    }
+}
+
+/// The definition of a single column of a table, as needed to create it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnDef {
+    pub name:     sstr,
+    pub kind:     TypeKind,
+    pub nullable: bool,
+}
+
+/// A table whose columns are known, which `#[derive(Tuna)]` implements.
+pub trait Table: Selectable {
+    /// The table's columns, in order.
+    const COLUMNS: &'static [ColumnDef];
+
+    /// Returns the `CREATE TABLE` statement for this table in the given dialect.
+    fn create_table<D: Dialect>(dialect: D) -> String {
+        let mut out = Renderer::new(&dialect);
+        out.push("CREATE TABLE ");
+        out.push_identifier(Self::default().sql());
+        out.push(" (");
+        for (i, column) in Self::COLUMNS.iter().enumerate() {
+            if i > 0 {
+                out.push(", ");
+            }
+            out.push_identifier(column.name);
+            out.push(" ");
+            out.push(dialect.type_name(column.kind));
+            if !column.nullable {
+                out.push(" NOT NULL");
+            }
+        }
+        out.push(")");
+        out.into_parts().0
+    }
+}
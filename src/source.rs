@@ -0,0 +1,373 @@
+//! SQL sources.
+//!
+//! This module contains what a query selects from: a single table, or tables joined together.
+//! That is, things that go after the `FROM` clause.
+//!
+//! Joining tables gives a `Joined` source whose type records every table in it, so columns
+//! from any of them can be projected, filtered and sorted on, while columns of tables that
+//! aren't part of the query are still rejected at compile time:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "users")]
+//! struct Users {
+//!     id:   Int,
+//!     name: Text,
+//! }
+//!
+//! #[derive(Tuna)]
+//! #[tuna(table = "orders")]
+//! struct Orders {
+//!     order_id: Int,
+//!     user_id:  Int,
+//!     total:    Double,
+//! }
+//!
+//! let query = USERS
+//!     .left_join(ORDERS).on(USER_ID.equals(ID))
+//!     .select((NAME, TOTAL))
+//!     .filter(NAME.starts_with("t"))
+//!     .finish(Sqlite);
+//!
+//! assert_eq!(query.sql, r#"SELECT "users"."name", "orders"."total" FROM "users" LEFT JOIN "orders" ON "orders"."user_id" = "users"."id" WHERE "users"."name" LIKE ? ESCAPE '!'"#);
+//!
+//! // Users without orders still have a row, so the columns of `orders` are optional.
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch(r#"CREATE TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL);
+//!                       CREATE TABLE orders (order_id INTEGER NOT NULL, user_id INTEGER NOT NULL, total REAL NOT NULL);
+//!                       INSERT INTO users VALUES (1, 'tuna'), (2, 'trout');
+//!                       INSERT INTO orders VALUES (1, 1, 9.5);"#)?;
+//!
+//! let rows: Vec<(String, Option<f64>)> = conn.fetch_all(&query)?;
+//! assert_eq!(rows, vec![("tuna".to_owned(), Some(9.5)), ("trout".to_owned(), None)]);
+//! # }
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+//!
+//! A column that is nullable to begin with isn't wrapped twice, since a missing row and a
+//! null value read the same:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! # }
+//! #[derive(Tuna)]
+//! #[tuna(table = "orders")]
+//! struct Orders {
+//!     user_id: Int,
+//!     note:    Nullable<Text>,
+//! }
+//!
+//! let query = USERS
+//!     .left_join(ORDERS).on(USER_ID.equals(ID))
+//!     .select((ID, NOTE))
+//!     .order_by(ID)
+//!     .finish(Sqlite);
+//!
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch(r#"CREATE TABLE users (id INTEGER NOT NULL);
+//!                       CREATE TABLE orders (user_id INTEGER NOT NULL, note TEXT);
+//!                       INSERT INTO users VALUES (1), (2), (3);
+//!                       INSERT INTO orders VALUES (1, 'fragile'), (2, NULL);"#)?;
+//!
+//! let rows: Vec<(i32, Option<String>)> = conn.fetch_all(&query)?;
+//! assert_eq!(rows, vec![(1, Some("fragile".to_owned())), (2, None), (3, None)]);
+//! # }
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+//!
+//! MySQL has no `FULL JOIN`, so a query with one can't be finished for it:
+//!
+//! ```compile_fail
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! # }
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "orders")]
+//! # struct Orders {
+//! #     user_id: Int,
+//! # }
+//! let query = USERS.full_join(ORDERS).on(USER_ID.equals(ID)).select((ID, USER_ID)).finish(MySql);
+//! ```
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! # }
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "orders")]
+//! # struct Orders {
+//! #     user_id: Int,
+//! # }
+//! let query = USERS.full_join(ORDERS).on(USER_ID.equals(ID)).select((ID, USER_ID)).finish(Postgres);
+//! assert_eq!(query.sql, r#"SELECT "users"."id", "orders"."user_id" FROM "users" FULL JOIN "orders" ON "orders"."user_id" = "users"."id""#);
+//! ```
+//!
+//! A table can only be joined once, since its columns couldn't tell which of the
+//! copies they belong to.
+
+use std::marker::PhantomData;
+use super::{
+  aggregate::OrNull,
+  builder::{Selectable, Selected, Distinct},
+  common::{Projection, sstr},
+  condition::Condition,
+  dialect::SupportsFullJoin,
+  render::{Render, Renderer}
+};
+
+/// Things that a query can select from.
+pub trait Source: Sized {
+    /// Whether there is more than one table in the source, in which case
+    /// columns have to be written along with the name of their table.
+    const JOINED: bool = false;
+
+    /// Writes the source as it appears after `FROM`.
+    fn render_source(&self, out: &mut Renderer);
+
+    /// Joins a table, keeping only the pairs of rows that meet the `on` condition.
+    fn join<R: Selectable>(self, right: R) -> Joining<Self, R, InnerJoin> {
+        Joining::new(self, right)
+    }
+
+    /// Joins a table, keeping the rows of this source that have no match.
+    fn left_join<R: Selectable>(self, right: R) -> Joining<Self, R, LeftJoin> {
+        Joining::new(self, right)
+    }
+
+    /// Joins a table, keeping the rows of the joined table that have no match.
+    fn right_join<R: Selectable>(self, right: R) -> Joining<Self, R, RightJoin> {
+        Joining::new(self, right)
+    }
+
+    /// Joins a table, keeping the rows of both sides that have no match.
+    fn full_join<R: Selectable>(self, right: R) -> Joining<Self, R, FullJoin> {
+        Joining::new(self, right)
+    }
+}
+
+impl<T: Selectable> Source for T {
+    fn render_source(&self, out: &mut Renderer) {
+        out.push_identifier(self.sql());
+    }
+}
+
+/// A join that is waiting for its `on` condition.
+pub struct Joining<L, R, K> {
+    left:  L,
+    right: R,
+    _kind: PhantomData<K>,
+}
+
+impl<L, R, K> Joining<L, R, K> {
+    fn new(left: L, right: R) -> Self {
+        Joining {
+            left,
+            right,
+            _kind: PhantomData,
+        }
+    }
+
+    /// Gives the condition that rows of the two sides are paired up by.
+    pub fn on<Cond, Idx>(self, condition: Cond) -> Joined<L, R, K, Cond>
+    where
+        Cond: Condition<Joined<L, R, K, Cond>, Idx> {
+
+        Joined {
+            left:  self.left,
+            right: self.right,
+            on:    condition,
+            _kind: PhantomData,
+        }
+    }
+}
+
+/// Two sources joined together. The query at this point is
+/// `self.left JOIN self.right ON self.on`.
+pub struct Joined<L, R, K, Cond> {
+    left:  L,
+    right: R,
+    on:    Cond,
+    _kind: PhantomData<K>,
+}
+
+impl<L, R, K, Cond> Joined<L, R, K, Cond> {
+    /// Begins a query on the joined tables by providing the desired projection,
+    /// which can take columns from any of them.
+    pub fn select<P, Idx>(self, p: P) -> Selected<Self, P>
+    where
+        P: Projection<Self, Idx> {
+
+        Selected {
+            source:     self,
+            projection: p,
+            distinct:   Distinct::All,
+        }
+    }
+}
+
+impl<L, R, K, Cond> Source for Joined<L, R, K, Cond>
+where
+    L: Source,
+    R: Source,
+    K: JoinKind,
+    Cond: Render {
+
+    const JOINED: bool = true;
+
+    fn render_source(&self, out: &mut Renderer) {
+        self.left.render_source(out);
+        out.push(K::SQL);
+        self.right.render_source(out);
+        out.push(" ON ");
+        out.render(&self.on);
+    }
+}
+
+/// The kinds of join, which decide whether either side can be missing
+/// from the joined rows.
+pub trait JoinKind {
+    /// Whether the left side's columns can be null after joining.
+    type Left: Nullness;
+
+    /// Whether the right side's columns can be null after joining.
+    type Right: Nullness;
+
+    /// The SQL between the two sides.
+    const SQL: sstr;
+}
+
+/// `INNER JOIN`: only rows that match on both sides.
+pub struct InnerJoin;
+
+/// `LEFT JOIN`: every row of the left side.
+pub struct LeftJoin;
+
+/// `RIGHT JOIN`: every row of the right side.
+pub struct RightJoin;
+
+/// `FULL JOIN`: every row of both sides.
+pub struct FullJoin;
+
+impl JoinKind for InnerJoin {
+    type Left = Required;
+    type Right = Required;
+    const SQL: sstr = " INNER JOIN ";
+}
+
+impl JoinKind for LeftJoin {
+    type Left = Required;
+    type Right = Optional;
+    const SQL: sstr = " LEFT JOIN ";
+}
+
+impl JoinKind for RightJoin {
+    type Left = Optional;
+    type Right = Required;
+    const SQL: sstr = " RIGHT JOIN ";
+}
+
+impl JoinKind for FullJoin {
+    type Left = Optional;
+    type Right = Optional;
+    const SQL: sstr = " FULL JOIN ";
+}
+
+/// A source that can be written in the dialect `D`. Every source can be,
+/// except for those with a `FULL JOIN` in dialects that have none.
+pub trait SourceFor<D> { }
+
+impl<T: Selectable, D> SourceFor<D> for T { }
+
+impl<L, R, K, Cond, D> SourceFor<D> for Joined<L, R, K, Cond>
+where
+    L: SourceFor<D>,
+    R: SourceFor<D>,
+    K: JoinKindFor<D> { }
+
+/// The kinds of join that can be written in the dialect `D`.
+pub trait JoinKindFor<D> { }
+
+impl<D> JoinKindFor<D> for InnerJoin { }
+impl<D> JoinKindFor<D> for LeftJoin { }
+impl<D> JoinKindFor<D> for RightJoin { }
+impl<D: SupportsFullJoin> JoinKindFor<D> for FullJoin { }
+
+/// Whether the columns of a table are always present in a source's rows,
+/// or can be missing because the table was outer joined.
+pub trait Nullness {
+    /// Missing if either this or `Other` is.
+    type Or<Other: Nullness>: Nullness;
+
+    /// The Rust type of a column whose values are `V` when present. A column
+    /// that is nullable already stays a single `Option` when it can be missing.
+    type Wrap<V: OrNull>;
+}
+
+/// The columns of a table are always present.
+pub struct Required;
+
+/// The columns of a table are missing from rows that had no match in an outer join.
+pub struct Optional;
+
+impl Nullness for Required {
+    type Or<Other: Nullness> = Other;
+    type Wrap<V: OrNull> = V;
+}
+
+impl Nullness for Optional {
+    type Or<Other: Nullness> = Optional;
+    type Wrap<V: OrNull> = V::Value;
+}
+
+/// A source that includes the table `Table`.
+///
+/// `Idx` is the path to the table within the source: `Here` for the table
+/// itself, and `Left` or `Right` to go down one side of a join. It never
+/// needs to be written out, since the compiler infers it from the only
+/// path that leads to the table.
+pub trait Includes<Table, Idx> {
+    /// Whether the table's columns can be missing from the source's rows.
+    type Nulls: Nullness;
+}
+
+/// The table is the source itself.
+pub struct Here;
+
+/// The table is on the left side of a join, at `Idx` within it.
+pub struct Left<Idx>(PhantomData<Idx>);
+
+/// The table is on the right side of a join, at `Idx` within it.
+pub struct Right<Idx>(PhantomData<Idx>);
+
+impl<T> Includes<T, Here> for T {
+    type Nulls = Required;
+}
+
+impl<L, R, K, Cond, T, Idx> Includes<T, Left<Idx>> for Joined<L, R, K, Cond>
+where
+    L: Includes<T, Idx>,
+    K: JoinKind {
+
+    type Nulls = <K::Left as Nullness>::Or<L::Nulls>;
+}
+
+impl<L, R, K, Cond, T, Idx> Includes<T, Right<Idx>> for Joined<L, R, K, Cond>
+where
+    R: Includes<T, Idx>,
+    K: JoinKind {
+
+    type Nulls = <K::Right as Nullness>::Or<R::Nulls>;
+}
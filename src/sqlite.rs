@@ -0,0 +1,157 @@
+//! SQLite backend.
+//!
+//! This module contains a `Connection` to an embedded SQLite database, either in a file or in
+//! memory. Queries run on it should be finished with the `Sqlite` dialect. It is only
+//! available with the `sqlite` feature, which is on by default.
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! #     name: Text,
+//! # }
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL);
+//!                     INSERT INTO users VALUES (1, 'tuna'), (2, 'o''brien');")?;
+//!
+//! let query = USERS
+//!     .select((ID, NAME))
+//!     .filter(NAME.equals("o'brien".to_owned()))
+//!     .finish(Sqlite);
+//!
+//! // Rows are decoded into the Rust types of the projected columns.
+//! assert_eq!(conn.fetch_all(&query)?, vec![(2, "o'brien".to_owned())]);
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+//!
+//! `fetch_optional` and `fetch_one` stop after the first row, so the rest are never read. Here
+//! the second row holds text that isn't UTF-8, which only `fetch_all` gets as far as reading:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     id: Int,
+//! #     name: Text,
+//! # }
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL);
+//!                     INSERT INTO users VALUES (1, 'tuna'), (2, CAST(x'ff' AS TEXT));")?;
+//!
+//! let query = USERS.select((ID, NAME)).order_by(ID).finish(Sqlite);
+//! assert_eq!(conn.fetch_optional(&query)?, Some((1, "tuna".to_owned())));
+//! assert_eq!(conn.fetch_one(&query)?, (1, "tuna".to_owned()));
+//! assert!(matches!(conn.fetch_all(&query), Err(Error::UnsupportedValue(_))));
+//!
+//! // With no rows, `fetch_optional` gives `None` and `fetch_one` fails.
+//! let query = USERS.select(NAME).filter(ID.greater_than(2)).finish(Sqlite);
+//! assert_eq!(conn.fetch_optional(&query)?, None);
+//! assert!(matches!(conn.fetch_one(&query), Err(Error::NoRows)));
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+
+use std::path::Path;
+use rusqlite::types::{ToSqlOutput, ValueRef};
+use super::{
+  builder::{Query, Statement},
+  common::Value,
+  connection::{Connection, Error, Row}
+};
+
+/// A connection to a SQLite database.
+pub struct SqliteConnection {
+    conn: rusqlite::Connection,
+}
+
+impl SqliteConnection {
+    /// Opens the database stored in the file at `path`, creating it if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(SqliteConnection { conn: rusqlite::Connection::open(path)? })
+    }
+
+    /// Opens a new database that only lives in memory.
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Ok(SqliteConnection { conn: rusqlite::Connection::open_in_memory()? })
+    }
+
+    /// Runs one or more raw SQL statements, separated by semicolons. This is
+    /// meant for things the builder doesn't cover, like setting up a schema.
+    pub fn execute_batch(&self, sql: &str) -> Result<(), Error> {
+        Ok(self.conn.execute_batch(sql)?)
+    }
+}
+
+impl Connection for SqliteConnection {
+    fn execute<Table>(&self, statement: &Statement<Table>) -> Result<usize, Error> {
+        let mut prepared = self.conn.prepare(&statement.sql)?;
+        Ok(prepared.execute(rusqlite::params_from_iter(&statement.params))?)
+    }
+
+    fn fetch_rows<Src, Prj>(&self, query: &Query<Src, Prj>) -> Result<Vec<Row>, Error> {
+        let mut statement = self.conn.prepare(&query.sql)?;
+        let width = statement.column_count();
+        let mut rows = statement.query(rusqlite::params_from_iter(&query.params))?;
+
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+            result.push(read_row(row, width)?);
+        }
+        Ok(result)
+    }
+
+    fn fetch_first_row<Src, Prj>(&self, query: &Query<Src, Prj>) -> Result<Option<Row>, Error> {
+        let mut statement = self.conn.prepare(&query.sql)?;
+        let width = statement.column_count();
+        let mut rows = statement.query(rusqlite::params_from_iter(&query.params))?;
+
+        match rows.next()? {
+            Some(row) => Ok(Some(read_row(row, width)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Reads the first `width` values of a row returned by SQLite.
+fn read_row(row: &rusqlite::Row, width: usize) -> Result<Row, Error> {
+    let mut values = Vec::with_capacity(width);
+    for i in 0..width {
+        values.push(from_sqlite(row.get_ref(i)?)?);
+    }
+    Ok(values)
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Self {
+        Error::Backend(Box::new(e))
+    }
+}
+
+impl rusqlite::ToSql for Value {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(match self {
+            Value::Null => ToSqlOutput::Borrowed(ValueRef::Null),
+            Value::Bool(a) => ToSqlOutput::Borrowed(ValueRef::Integer(*a as i64)),
+            Value::Int(a) => ToSqlOutput::Borrowed(ValueRef::Integer(*a)),
+            Value::Real(a) => ToSqlOutput::Borrowed(ValueRef::Real(*a)),
+            Value::Text(a) => ToSqlOutput::Borrowed(ValueRef::Text(a.as_bytes())),
+            Value::Blob(a) => ToSqlOutput::Borrowed(ValueRef::Blob(a)),
+        })
+    }
+}
+
+/// Converts a value read from SQLite into a `Value`.
+fn from_sqlite(value: ValueRef) -> Result<Value, Error> {
+    Ok(match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(a) => Value::Int(a),
+        ValueRef::Real(a) => Value::Real(a),
+        ValueRef::Text(a) => match std::str::from_utf8(a) {
+            Ok(text) => Value::Text(text.to_owned()),
+            Err(_) => return Err(Error::UnsupportedValue("text that isn't UTF-8".to_owned())),
+        },
+        ValueRef::Blob(a) => Value::Blob(a.to_vec()),
+    })
+}
@@ -0,0 +1,290 @@
+//! SQL updates.
+//!
+//! This module contains the builder for `UPDATE` statements. Each `set` assigns a column a
+//! value of its own Rust type, another column of the table, or an arithmetic expression, and
+//! the rows to update are chosen with the same conditions as a query's `WHERE`:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! #[derive(Tuna)]
+//! #[tuna(table = "users")]
+//! struct Users {
+//!     id:          Int,
+//!     name:        Text,
+//!     login_count: Int,
+//! }
+//!
+//! let statement = update(USERS)
+//!     .set(NAME, "tuna".to_owned())
+//!     .set(LOGIN_COUNT, LOGIN_COUNT + 1)
+//!     .filter(ID.equals(1))
+//!     .finish(Sqlite);
+//!
+//! assert_eq!(statement.sql, r#"UPDATE "users" SET "name" = ?, "login_count" = "users"."login_count" + ? WHERE "users"."id" = ?"#);
+//!
+//! # #[cfg(feature = "sqlite")] {
+//! let conn = SqliteConnection::open_in_memory()?;
+//! conn.execute_batch("CREATE TABLE users (id INTEGER NOT NULL, name TEXT NOT NULL, login_count INTEGER NOT NULL);
+//!                     INSERT INTO users VALUES (1, 'trout', 4), (2, 'cod', 0);")?;
+//! assert_eq!(conn.execute(&statement)?, 1);
+//!
+//! let query = USERS.select((NAME, LOGIN_COUNT)).order_by(ID).finish(Sqlite);
+//! assert_eq!(conn.fetch_all(&query)?, vec![("tuna".to_owned(), 5), ("cod".to_owned(), 0)]);
+//! # }
+//! # Ok::<(), tuna::connection::Error>(())
+//! ```
+//!
+//! An update has to be filtered before it can be finished, so that every row of a table
+//! can't be changed by forgetting to. Updating every row takes an explicit `all_rows()`:
+//!
+//! ```
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     login_count: Int,
+//! # }
+//! let statement = update(USERS).set(LOGIN_COUNT, 0).all_rows().finish(Postgres);
+//! assert_eq!(statement.sql, r#"UPDATE "users" SET "login_count" = $1"#);
+//! ```
+//!
+//! ```compile_fail
+//! # use tuna::builder::*;
+//! # #[derive(Tuna)]
+//! # #[tuna(table = "users")]
+//! # struct Users {
+//! #     login_count: Int,
+//! # }
+//! let statement = update(USERS).set(LOGIN_COUNT, 0).finish(Postgres);
+//! ```
+
+use super::{
+  builder::{Selectable, WriteStatement},
+  column::Column,
+  condition::{Condition, Predicate, Operand, InScope, Both, Either},
+  render::{Render, Renderer}
+};
+
+/// Begins an `UPDATE` of the given table.
+pub fn update<Table: Selectable>(table: Table) -> Updating<Table> {
+    Updating { table }
+}
+
+/// An update that is waiting for the columns it sets. The statement at
+/// this point is `UPDATE self.table`.
+pub struct Updating<Table> {
+    table: Table,
+}
+
+impl<Table> Updating<Table> {
+    /// Sets a column to a value of its type, or to an expression computed
+    /// from the columns of the table.
+    pub fn set<Type, Expr, Idx>(self, column: Column<Table, Type>, value: Expr) -> Update<Table, Assignment<Table, Type, Expr>>
+    where
+        Expr: Operand<Type> + InScope<Table, Idx> {
+
+        Update {
+            table:       self.table,
+            assignments: Assignment { column, value },
+        }
+    }
+}
+
+/// An update that has yet to be filtered. The statement at this point
+/// is `UPDATE self.table SET self.assignments`.
+pub struct Update<Table, Set> {
+    table:       Table,
+    assignments: Set,
+}
+
+impl<Table, Set> Update<Table, Set> {
+    /// Sets another column.
+    pub fn set<Type, Expr, Idx>(self, column: Column<Table, Type>, value: Expr) -> Update<Table, (Set, Assignment<Table, Type, Expr>)>
+    where
+        Expr: Operand<Type> + InScope<Table, Idx> {
+
+        Update {
+            table:       self.table,
+            assignments: (self.assignments, Assignment { column, value }),
+        }
+    }
+
+    /// Only updates the rows that meet some given condition.
+    pub fn filter<Cond, Idx>(self, condition: Cond) -> FilteredUpdate<Table, Set, Cond>
+    where
+        Cond: Condition<Table, Idx> {
+
+        FilteredUpdate {
+            update: self,
+            condition,
+        }
+    }
+
+    /// Updates every row of the table.
+    pub fn all_rows(self) -> UpdateAll<Table, Set> {
+        UpdateAll { update: self }
+    }
+}
+
+impl<Table, Set> Render for Update<Table, Set>
+where
+    Table: Selectable,
+    Set: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.push("UPDATE ");
+        out.push_identifier(self.table.sql());
+        out.push(" SET ");
+        out.render(&self.assignments);
+    }
+}
+
+/// Columns being set, as a `(Column, value)` pair or a tuple of those. Each value
+/// can be anything the column could be `set` to.
+pub trait Assignments<Table, Idx> {
+    /// The assignments, rendered as `column = value` separated by commas.
+    type Set: Render;
+
+    /// Gives the assignments, ready to be rendered.
+    fn into_assignments(self) -> Self::Set;
+}
+
+impl<Table, Type, Expr, Idx> Assignments<Table, Idx> for (Column<Table, Type>, Expr)
+where
+    Expr: Operand<Type> + InScope<Table, Idx> {
+
+    type Set = Assignment<Table, Type, Expr>;
+
+    fn into_assignments(self) -> Self::Set {
+        let (column, value) = self;
+        Assignment { column, value }
+    }
+}
+
+/// Allows assigning tuples of pairs like ((A, a), (B, b)), ((A, a), (B, b), (C, c)) ...
+macro_rules! impl_assignments_for_tuple {
+    ($(($tv:ident $te:ident $ti:ident)),* $(,)?) => {
+        impl<Table, $($tv, $te, $ti),*> Assignments<Table, ($($ti,)*)> for ($((Column<Table, $tv>, $te),)*)
+        where
+            $( (Column<Table, $tv>, $te): Assignments<Table, $ti>, )* {
+
+            type Set = ($( <(Column<Table, $tv>, $te) as Assignments<Table, $ti>>::Set, )*);
+
+            #[allow(non_snake_case)]
+            fn into_assignments(self) -> Self::Set {
+                let ($($tv,)*) = self;
+                ($( Assignments::<Table, $ti>::into_assignments($tv), )*)
+            }
+        }
+    };
+}
+
+apply_macro_for_tuples! {
+    impl_assignments_for_tuple! {
+        [(A EA IA) (B EB IB)]
+        (C EC IC) (D ED ID) (E EE IE) (F EF IF) (G EG IG) (H EH IH) (I EI II) (J EJ IJ)
+        (K EK IK) (L EL IL) (M EM IM) (N EN IN) (O EO IO) (P EP IP) (Q EQ IQ) (R ER IR)
+        (S ES IS) (T ET IT) (U EU IU) (V EV IV) (W EW IW) (X EX IX) (Y EY IY) (Z EZ IZ)
+    }
+}
+
+/// A column being set to a value, as `column = value`. Several of them are
+/// chained together in tuples, which render them separated by commas.
+pub struct Assignment<Table, Type, Expr> {
+    pub column: Column<Table, Type>,
+    pub value:  Expr,
+}
+
+impl<Table, Type, Expr> Render for Assignment<Table, Type, Expr>
+where
+    Expr: Operand<Type> {
+
+    fn render(&self, out: &mut Renderer) {
+        // The column being set can't be written along with its table.
+        out.push_identifier(self.column.name);
+        out.push(" = ");
+        self.value.render_operand(out);
+    }
+}
+
+/// The result of filtering an update. The statement at this point is
+/// `self.update WHERE self.condition`.
+pub struct FilteredUpdate<Table, Set, Cond> {
+    update:    Update<Table, Set>,
+    condition: Cond,
+}
+
+impl<Table, Set, Cond> FilteredUpdate<Table, Set, Cond>
+where
+    Cond: Predicate {
+
+    /// Narrows the update to the rows that meet both the conditions so far
+    /// and the given one.
+    pub fn filter<Other, Idx>(self, condition: Other) -> FilteredUpdate<Table, Set, Both<Cond, Other>>
+    where
+        Other: Condition<Table, Idx> {
+
+        FilteredUpdate {
+            update:    self.update,
+            condition: self.condition.and(condition),
+        }
+    }
+
+    /// Widens the update to the rows that meet either the conditions so far
+    /// or the given one.
+    pub fn or_filter<Other, Idx>(self, condition: Other) -> FilteredUpdate<Table, Set, Either<Cond, Other>>
+    where
+        Other: Condition<Table, Idx> {
+
+        FilteredUpdate {
+            update:    self.update,
+            condition: self.condition.or(condition),
+        }
+    }
+}
+
+impl<Table, Set, Cond> Render for FilteredUpdate<Table, Set, Cond>
+where
+    Table: Selectable,
+    Set: Render,
+    Cond: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.update);
+        out.push(" WHERE ");
+        out.render(&self.condition);
+    }
+}
+
+impl<Table, Set, Cond> WriteStatement for FilteredUpdate<Table, Set, Cond>
+where
+    Table: Selectable,
+    Set: Render,
+    Cond: Render {
+
+    type Table = Table;
+}
+
+/// An update of every row of a table. The statement at this point
+/// is the same as `self.update`.
+pub struct UpdateAll<Table, Set> {
+    update: Update<Table, Set>,
+}
+
+impl<Table, Set> Render for UpdateAll<Table, Set>
+where
+    Table: Selectable,
+    Set: Render {
+
+    fn render(&self, out: &mut Renderer) {
+        out.render(&self.update);
+    }
+}
+
+impl<Table, Set> WriteStatement for UpdateAll<Table, Set>
+where
+    Table: Selectable,
+    Set: Render {
+
+    type Table = Table;
+}
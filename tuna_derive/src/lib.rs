@@ -1,28 +1,218 @@
 extern crate proc_macro;
+extern crate proc_macro2;
 extern crate syn;
 #[macro_use]
 extern crate quote;
 
 use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{Data, Fields, Ident, LitStr, Path};
+use syn::ext::IdentExt;
 
-#[proc_macro_derive(Tuna)]
+/// Generates the code needed to query a table from its definition: a
+/// constant for the table, a `Column` constant for each field, and the
+/// `Default`, `ToSql`, `Selectable` and `Table` implementations. Each
+/// field's type must be a `SqlType`.
+///
+/// The table is named after the struct unless renamed with
+/// `#[tuna(table = "name")]`.
+#[proc_macro_derive(Tuna, attributes(tuna))]
 pub fn tuna_derive(input: TokenStream) -> TokenStream {
     // Construct a represntation of Rust code as a syntax tree
     // that we can manipulate
     let ast = syn::parse(input).unwrap();
 
-    // Build the trait implementation
-    impl_tuna(&ast)
+    // Build the trait implementations
+    match impl_tuna(&ast) {
+        Ok(gen) => gen.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
 }
 
-fn impl_tuna(ast: &syn::DeriveInput) -> TokenStream {
+fn impl_tuna(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &ast.ident;
-    let gen = quote! {
-        impl Tuna for #name {
-            fn hello_macro() {
-                println!("Hello, Macro! My name is {}", stringify!(#name));
+    let vis = &ast.vis;
+
+    let fields = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(ast, "Tuna tables must have named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(ast, "Tuna can only be derived for structs")),
+    };
+
+    if !ast.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&ast.generics, "Tuna tables can't be generic"));
+    }
+
+    // Raw identifiers like `r#type` name the column or table `type`.
+    let table = table_name(ast)?.unwrap_or_else(|| name.unraw().to_string());
+    let table_const = Ident::new(&name.unraw().to_string().to_uppercase(), name.span());
+
+    let field_names: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+    let column_defs = fields.iter().map(|field| {
+        let ty = &field.ty;
+        let column = field.ident.as_ref().unwrap().unraw().to_string();
+
+        quote! {
+            ::tuna::builder::ColumnDef {
+                name:     #column,
+                kind:     <#ty as ::tuna::builder::SqlType>::KIND,
+                nullable: <#ty as ::tuna::builder::SqlType>::NULLABLE,
+            }
+        }
+    });
+
+    let columns = fields.iter().enumerate().map(|(position, field)| {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let column = ident.unraw().to_string();
+        let column_const = Ident::new(&column.to_uppercase(), Span::call_site());
+
+        quote! {
+            #[allow(dead_code)]
+            #vis const #column_const: ::tuna::builder::Column<
+                #name,
+                <#ty as ::tuna::builder::SqlType>::Value
+            > = ::tuna::builder::Column {
+                name:          #column,
+                position:      #position,
+                _table_marker: ::std::marker::PhantomData,
+            };
+        }
+    });
+
+    Ok(quote! {
+        #[allow(dead_code)]
+        #vis const #table_const: #name = #name {
+            #( #field_names: <#field_types as ::tuna::builder::SqlType>::MARKER, )*
+        };
+
+        #( #columns )*
+
+        impl ::std::default::Default for #name {
+            fn default() -> Self {
+                // The fields are only markers, so rebuilding the table from
+                // them is what keeps them from being reported as unused.
+                let #name { #( #field_names, )* } = #table_const;
+                #name { #( #field_names, )* }
             }
         }
+
+        impl ::tuna::builder::ToSql for #name {
+            type Sql = ::tuna::builder::sstr;
+
+            fn sql(&self) -> Self::Sql {
+                #table
+            }
+        }
+
+        impl ::tuna::builder::Selectable for #name { }
+
+        impl ::tuna::builder::Table for #name {
+            const COLUMNS: &'static [::tuna::builder::ColumnDef] = &[ #( #column_defs, )* ];
+        }
+    })
+}
+
+/// Generates an `Insertable` implementation for a struct holding the values
+/// of a row, so that it can be inserted into the table given by
+/// `#[tuna(insert_into = Table)]`. Each field sets the column of the same
+/// name, whose constant has to be in scope, and must be of the column's
+/// Rust type.
+#[proc_macro_derive(Insertable, attributes(tuna))]
+pub fn insertable_derive(input: TokenStream) -> TokenStream {
+    let ast = syn::parse(input).unwrap();
+
+    match impl_insertable(&ast) {
+        Ok(gen) => gen.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn impl_insertable(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+
+    let fields = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(ast, "Insertable rows must have named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(ast, "Insertable can only be derived for structs")),
     };
-    gen.into()
-}
\ No newline at end of file
+
+    if fields.is_empty() {
+        return Err(syn::Error::new_spanned(ast, "Insertable rows must set at least one column"));
+    }
+
+    let table = match insert_target(ast)? {
+        Some(table) => table,
+        None => return Err(syn::Error::new_spanned(ast, "Insertable needs a `#[tuna(insert_into = Table)]` attribute")),
+    };
+
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let field_names: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let column_consts: Vec<_> = field_names.iter()
+        .map(|ident| Ident::new(&ident.unraw().to_string().to_uppercase(), ident.span()))
+        .collect();
+
+    Ok(quote! {
+        impl #impl_generics ::tuna::builder::Insertable<#table> for #name #ty_generics #where_clause {
+            fn columns(&self) -> ::std::vec::Vec<::tuna::builder::sstr> {
+                ::std::vec![ #( #column_consts.name, )* ]
+            }
+
+            fn values(&self) -> ::std::vec::Vec<::tuna::builder::Value> {
+                ::std::vec![ #( ::tuna::builder::insert_value::<#table, _>(#column_consts, &self.#field_names), )* ]
+            }
+        }
+    })
+}
+
+/// Finds the table name given by a `#[tuna(table = "...")]` attribute, if any.
+fn table_name(ast: &syn::DeriveInput) -> syn::Result<Option<String>> {
+    let mut table = None;
+
+    for attr in &ast.attrs {
+        if !attr.path().is_ident("tuna") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("table") {
+                let lit: LitStr = meta.value()?.parse()?;
+                table = Some(lit.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown tuna attribute"))
+            }
+        })?;
+    }
+
+    Ok(table)
+}
+
+/// Finds the table given by a `#[tuna(insert_into = Table)]` attribute, if any.
+fn insert_target(ast: &syn::DeriveInput) -> syn::Result<Option<Path>> {
+    let mut table = None;
+
+    for attr in &ast.attrs {
+        if !attr.path().is_ident("tuna") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("insert_into") {
+                table = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown tuna attribute"))
+            }
+        })?;
+    }
+
+    Ok(table)
+}